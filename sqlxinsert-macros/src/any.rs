@@ -0,0 +1,203 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attr::ContainerAttributes;
+use crate::field::{self, backtick_identifier, Field};
+use crate::{bound_generics, insertable_impl, query, reselect_keys, table_const, table_method};
+
+/// Columns of insert pushed at runtime as quoting depends on connected database
+///
+/// `dollar` is true for Postgres and SQLite, false for MySQL ( backtick quoting )
+fn column_pushes(fields: &[&Field]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident;
+            let column = &field.column;
+            let mysql_column = backtick_identifier(&field.name);
+            let push = quote! {
+                columns.push(if dollar { #column } else { #mysql_column });
+            };
+            if field.skip_none {
+                quote! {
+                    if self.#ident.is_some() {
+                        #push
+                    }
+                }
+            } else {
+                push
+            }
+        })
+        .collect()
+}
+
+/// `AnyKind` check of databases with `$1` placeholders and `returning *`
+///
+/// Only kinds of enabled features are matched ( `AnyKind` has no variant for disabled database ).
+fn dollar_kind() -> TokenStream2 {
+    #[allow(unused_mut)]
+    let mut kinds: Vec<TokenStream2> = Vec::new();
+    #[cfg(feature = "postgres")]
+    kinds.push(quote! { sqlx::any::AnyKind::Postgres });
+    #[cfg(feature = "sqlite")]
+    kinds.push(quote! { sqlx::any::AnyKind::Sqlite });
+
+    // `kind` is still read so it is not an unused variable in expanded code
+    if kinds.is_empty() {
+        return quote! { { let _ = &kind; false } };
+    }
+    quote! { matches!(kind, #( #kinds )|*) }
+}
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attributes = ContainerAttributes::from_ast(&input)?;
+
+    let all_fields = field::fields(&input, &attributes)?;
+    let fields = field::insert_fields(&all_fields);
+    let binds = query::bind_fields(&fields);
+    let pushes = column_pushes(&fields);
+    let dollar = dollar_kind();
+
+    let struct_name = &input.ident;
+    let generics = bound_generics(&input, &quote! { sqlx::Any });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Databases without `returning` select inserted row by primary key value or by `last_insert_id()`
+    let (key_where, key_values) = reselect_keys(&all_fields);
+
+    let table_const = table_const(&attributes);
+    let insert_query = table_method(&attributes, "insert_query");
+    let insert_raw = table_method(&attributes, "insert_raw");
+    let insert = table_method(&attributes, "insert");
+
+    // Table-less variants when table is known at compile time
+    let table_methods = match &attributes.table {
+        Some(_) => quote! {
+            pub fn insert_query(&self, kind: sqlx::any::AnyKind) -> String
+            {
+                self.#insert_query(kind, Self::TABLE)
+            }
+
+            pub async fn insert_raw<'acquire>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Any>) -> ::sqlxinsert::Result<sqlx::any::AnyQueryResult>
+            {
+                self.#insert_raw(conn, Self::TABLE).await
+            }
+
+            pub async fn insert<'acquire, Row>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Any>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::any::AnyRow>,
+                Row: std::marker::Unpin
+            {
+                self.#insert(conn, Self::TABLE).await
+            }
+        },
+        None => quote! {},
+    };
+
+    // Insert statement for `kind` and `table` in scope
+    let insert_sql = quote! {
+        {
+            let dollar = #dollar;
+            let table = if dollar { table.to_string() } else { table.backtick_quoted() };
+
+            #[allow(unused_mut)]
+            let mut columns: Vec<&str> = Vec::new();
+            #( #pushes )*
+
+            if columns.is_empty() {
+                match dollar {
+                    true => format!("insert into {} default values", table),
+                    false => format!("insert into {} () values ()", table),
+                }
+            } else {
+                let values = (1..columns.len() + 1)
+                    .map(|i| if dollar { format!("${}", i) } else { "?".to_string() })
+                    .collect::<Vec<String>>()
+                    .join(",");
+
+                format!("insert into {} ( {} ) values ( {} )", table, columns.join(", "), values)
+            }
+        }
+    };
+
+    // Insert returning row with `conn` and `table` in scope ( shared with `Insertable` )
+    let insert_body = quote! {
+        let mut conn = conn.acquire().await?;
+        let kind = conn.kind();
+
+        if #dollar {
+            let sql = format!("{} returning *", #insert_sql);
+            let query = sqlx::query_as::<_,Row>(&sql);
+            #( #binds )*
+            return Ok(query.fetch_one(&mut *conn).await?);
+        }
+
+        // Executed on acquired connection directly ( `insert_raw` takes `sqlx::Acquire` )
+        let sql = #insert_sql;
+        let query = sqlx::query(&sql);
+        #( #binds )*
+        let res = query.execute(&mut *conn).await?;
+
+        let sql = format!("select * from {} where {}", table.backtick_quoted(), #key_where);
+        let query = sqlx::query_as::<_,Row>(&sql);
+        #( let query = query.bind(#key_values); )*
+        let res: Row = query
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(res)
+    };
+
+    let insertable = insertable_impl(
+        &input,
+        &attributes,
+        quote! { sqlx::Any },
+        fields.iter().map(|field| field.column.clone()).collect(),
+        &binds,
+        insert_body.clone(),
+    );
+    if attributes.trait_only {
+        return Ok(insertable);
+    }
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #table_const
+
+            /// Postgres and SQLite get `$1` placeholders, MySQL `?` placeholders and backtick quoting
+            pub fn #insert_query(&self, kind: sqlx::any::AnyKind, table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                #insert_sql
+            }
+
+            pub async fn #insert_raw<'acquire>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Any>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::any::AnyQueryResult>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let mut conn = conn.acquire().await?;
+                let sql = self.#insert_query(conn.kind(), &table);
+                let query = sqlx::query(&sql);
+                #( #binds )*
+                Ok(query.execute(&mut *conn).await?)
+            }
+
+            /// Inserted row is returned with `returning *` ( Postgres, SQLite 3.35+ ) or selected again
+            /// by primary key ( `last_insert_id()` for MySQL AUTO_INCREMENT )
+            pub async fn #insert<'acquire, Row>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Any>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::any::AnyRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                #insert_body
+            }
+
+            #table_methods
+        }
+
+        #insertable
+    })
+}
@@ -0,0 +1,272 @@
+use heck::{ToKebabCase, ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
+use syn::{Attribute, DeriveInput, Error, Lit, Meta, NestedMeta, Result};
+
+/// Struct level `#[sqlxinsert(...)]` attributes
+///
+/// ```rust,ignore
+/// #[sqlxinsert(table = "cars", rename_all = "camelCase")]
+/// struct Car { ... }
+/// ```
+#[derive(Default)]
+pub(crate) struct ContainerAttributes {
+    /// Table name fixed at compile time
+    pub table: Option<String>,
+    /// Column naming applied to all fields ( `#[sqlx(rename_all)]` is honored too )
+    pub rename_all: Option<RenameAll>,
+    /// `skip_none` for all `Option` fields
+    pub skip_none: bool,
+    /// Postgres `insert_unnest` ( all fields have to be `Clone` with array type )
+    pub unnest: bool,
+    /// Only `Insertable` impl without inherent methods
+    pub trait_only: bool,
+}
+
+impl ContainerAttributes {
+    pub fn from_ast(input: &DeriveInput) -> Result<Self> {
+        let mut attributes = ContainerAttributes::default();
+
+        for meta in sqlxinsert_metas(&input.attrs)? {
+            match meta {
+                Meta::NameValue(nv) if nv.path.is_ident("table") => {
+                    attributes.table = Some(lit_str(&nv.lit, "table")?);
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("rename_all") => {
+                    attributes.rename_all = Some(RenameAll::from_lit(&nv.lit)?);
+                }
+                Meta::Path(path) if path.is_ident("skip_none") => attributes.skip_none = true,
+                Meta::Path(path) if path.is_ident("unnest") => attributes.unnest = true,
+                Meta::Path(path) if path.is_ident("trait_only") => attributes.trait_only = true,
+                other => {
+                    return Err(Error::new_spanned(
+                        other.path(),
+                        format!(
+                            "unknown sqlxinsert struct attribute `{}`",
+                            path_name(other.path())
+                        ),
+                    ))
+                }
+            }
+        }
+
+        if attributes.rename_all.is_none() {
+            let rename_all = sqlx_metas(&input.attrs)
+                .into_iter()
+                .find_map(|meta| match meta {
+                    Meta::NameValue(nv) if nv.path.is_ident("rename_all") => Some(nv.lit),
+                    _ => None,
+                });
+            if let Some(lit) = rename_all {
+                attributes.rename_all = Some(RenameAll::from_lit(&lit)?);
+            }
+        }
+
+        Ok(attributes)
+    }
+}
+
+/// Field level `#[sqlxinsert(...)]` attributes
+///
+/// ```rust,ignore
+/// struct Car {
+///     #[sqlxinsert(primary_key, default)]
+///     pub id: i32,
+///     #[sqlxinsert(rename = "type")]
+///     pub kind: String,
+///     #[sqlxinsert(unique)]
+///     pub vin: String,
+/// }
+/// ```
+#[derive(Default)]
+pub(crate) struct FieldAttributes {
+    /// Column name ( `#[sqlx(rename)]` is honored too )
+    pub rename: Option<String>,
+    /// Not a database column
+    pub skip: bool,
+    /// Column value is provided by database on insert ( `SERIAL`, `DEFAULT now()`, ... )
+    pub default: bool,
+    /// `Option` column is inserted only when `Some` so `None` falls back to database default
+    pub skip_none: bool,
+    /// Column used in `where` clause of update ( several fields form composite key )
+    pub primary_key: bool,
+    /// Column of `on conflict ( ... )` target for upsert
+    pub conflict_key: bool,
+    /// Unique column with `find_by_<field>` returning single row
+    pub unique: bool,
+    /// Indexed column with `find_all_by_<field>` returning all matching rows
+    pub lookup: bool,
+    /// Unique column ordering `page` instead of primary key
+    pub order_key: bool,
+    /// Integer column checked and incremented by update ( optimistic locking )
+    pub version: bool,
+    /// Postgres type name for array binds ( `insert_unnest` )
+    pub pg_type: Option<String>,
+}
+
+impl FieldAttributes {
+    pub fn from_ast(field: &syn::Field) -> Result<Self> {
+        let mut attributes = FieldAttributes::default();
+
+        for meta in sqlxinsert_metas(&field.attrs)? {
+            match meta {
+                Meta::NameValue(nv) if nv.path.is_ident("rename") => {
+                    attributes.rename = Some(lit_str(&nv.lit, "rename")?);
+                }
+                Meta::NameValue(nv) if nv.path.is_ident("pg_type") => {
+                    attributes.pg_type = Some(lit_str(&nv.lit, "pg_type")?);
+                }
+                Meta::Path(path) if path.is_ident("skip") => attributes.skip = true,
+                Meta::Path(path) if path.is_ident("default") => attributes.default = true,
+                Meta::Path(path) if path.is_ident("skip_none") => attributes.skip_none = true,
+                Meta::Path(path) if path.is_ident("primary_key") => attributes.primary_key = true,
+                Meta::Path(path) if path.is_ident("conflict_key") => attributes.conflict_key = true,
+                Meta::Path(path) if path.is_ident("unique") => attributes.unique = true,
+                Meta::Path(path) if path.is_ident("lookup") => attributes.lookup = true,
+                Meta::Path(path) if path.is_ident("order_key") => attributes.order_key = true,
+                Meta::Path(path) if path.is_ident("version") => attributes.version = true,
+                other => {
+                    return Err(Error::new_spanned(
+                        other.path(),
+                        format!(
+                            "unknown sqlxinsert field attribute `{}`",
+                            path_name(other.path())
+                        ),
+                    ))
+                }
+            }
+        }
+
+        if attributes.rename.is_none() {
+            let rename = sqlx_metas(&field.attrs)
+                .into_iter()
+                .find_map(|meta| match meta {
+                    Meta::NameValue(nv) if nv.path.is_ident("rename") => Some(nv.lit),
+                    _ => None,
+                });
+            if let Some(lit) = rename {
+                attributes.rename = Some(lit_str(&lit, "rename")?);
+            }
+        }
+
+        Ok(attributes)
+    }
+}
+
+/// Same values as `#[sqlx(rename_all = "...")]`
+#[allow(clippy::enum_variant_names)]
+pub(crate) enum RenameAll {
+    LowerCase,
+    SnakeCase,
+    UpperCase,
+    ScreamingSnakeCase,
+    KebabCase,
+    CamelCase,
+    PascalCase,
+}
+
+impl RenameAll {
+    fn from_lit(lit: &Lit) -> Result<Self> {
+        let value = lit_str(lit, "rename_all")?;
+        let rename_all = match value.as_str() {
+            "lowercase" => RenameAll::LowerCase,
+            "snake_case" => RenameAll::SnakeCase,
+            "UPPERCASE" => RenameAll::UpperCase,
+            "SCREAMING_SNAKE_CASE" => RenameAll::ScreamingSnakeCase,
+            "kebab-case" => RenameAll::KebabCase,
+            "camelCase" => RenameAll::CamelCase,
+            "PascalCase" => RenameAll::PascalCase,
+            _ => {
+                return Err(Error::new_spanned(
+                    lit,
+                    format!("unexpected value for rename_all `{}`", value),
+                ))
+            }
+        };
+        Ok(rename_all)
+    }
+
+    /// car_name -> carName ( camelCase )
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            RenameAll::LowerCase => name.to_lowercase(),
+            RenameAll::SnakeCase => name.to_snake_case(),
+            RenameAll::UpperCase => name.to_uppercase(),
+            RenameAll::ScreamingSnakeCase => name.to_shouty_snake_case(),
+            RenameAll::KebabCase => name.to_kebab_case(),
+            RenameAll::CamelCase => name.to_lower_camel_case(),
+            RenameAll::PascalCase => name.to_upper_camel_case(),
+        }
+    }
+}
+
+/// `#[sqlxinsert(a, b = "c")]` -> [ a, b = "c" ]
+fn sqlxinsert_metas(attrs: &[Attribute]) -> Result<Vec<Meta>> {
+    let mut metas = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("sqlxinsert")) {
+        match attr.parse_meta()? {
+            Meta::List(list) => {
+                for nested in list.nested {
+                    match nested {
+                        NestedMeta::Meta(meta) => metas.push(meta),
+                        NestedMeta::Lit(lit) => {
+                            return Err(Error::new(
+                                lit.span(),
+                                "expected `#[sqlxinsert(key = \"value\")]`",
+                            ))
+                        }
+                    }
+                }
+            }
+            other => return Err(Error::new_spanned(other, "expected `#[sqlxinsert(...)]`")),
+        }
+    }
+    Ok(metas)
+}
+
+/// `#[sqlx(...)]` attributes shared with `sqlx::FromRow`, unknown ones are ignored
+fn sqlx_metas(attrs: &[Attribute]) -> Vec<Meta> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("sqlx"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|nested| match nested {
+            NestedMeta::Meta(meta) => Some(meta),
+            NestedMeta::Lit(_) => None,
+        })
+        .collect()
+}
+
+fn lit_str(lit: &Lit, name: &str) -> Result<String> {
+    match lit {
+        Lit::Str(s) => Ok(s.value()),
+        _ => Err(Error::new_spanned(
+            lit,
+            format!("expected `{} = \"...\"`", name),
+        )),
+    }
+}
+
+fn path_name(path: &syn::Path) -> String {
+    path.get_ident()
+        .map(|ident| ident.to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rename_all_test() {
+        assert_eq!(RenameAll::CamelCase.apply("car_name"), "carName");
+        assert_eq!(RenameAll::PascalCase.apply("car_name"), "CarName");
+        assert_eq!(RenameAll::KebabCase.apply("car_name"), "car-name");
+        assert_eq!(RenameAll::ScreamingSnakeCase.apply("car_name"), "CAR_NAME");
+        assert_eq!(RenameAll::UpperCase.apply("car_name"), "CAR_NAME");
+        assert_eq!(RenameAll::LowerCase.apply("CarName"), "carname");
+        assert_eq!(RenameAll::SnakeCase.apply("CarName"), "car_name");
+    }
+}
@@ -0,0 +1,485 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, DeriveInput, Error, Index, Type};
+
+use crate::attr::ContainerAttributes;
+use crate::field::{self, Field};
+use crate::{bound_generics, cached_sql, query, table_method};
+
+/// Parameter type of lookup by field: `String` -> `&str`, other types as declared
+fn lookup_type(ty: &Type) -> TokenStream2 {
+    if let Type::Path(path) = ty {
+        let is_string = path.qself.is_none()
+            && path
+                .path
+                .segments
+                .last()
+                .map(|segment| segment.ident == "String" && segment.arguments.is_empty())
+                .unwrap_or(false);
+        if is_string {
+            return quote! { &str };
+        }
+    }
+    quote! { #ty }
+}
+
+/// `find_by_<field>` of `#[sqlxinsert(unique)]` and `find_all_by_<field>` of `#[sqlxinsert(lookup)]`
+///
+/// Matching rows of `find_all_by_<field>` are ordered by `key_order` ( primary key columns ).
+fn lookup_methods(
+    attributes: &ContainerAttributes,
+    db: &TokenStream2,
+    fields: &[Field],
+    key_order: &str,
+) -> Vec<TokenStream2> {
+    let mut methods = Vec::new();
+
+    for field in fields {
+        let name = field.ident.unraw();
+        let column = &field.column;
+        let ty = lookup_type(field.ty);
+
+        if field.unique {
+            let method_name = format!("find_by_{}", name);
+            let find_by = table_method(attributes, &method_name);
+            let sql = cached_sql(
+                true,
+                quote! { format!("select * from {} where {} = $1", table, #column) },
+            );
+            let table_method = attributes.table.as_ref().map(|table| {
+                let method = format_ident!("{}", method_name);
+                quote! {
+                    pub async fn #method<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, value: #ty) -> ::sqlxinsert::Result<Option<Row>>
+                    where
+                        Row: Send,
+                        Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                        Row: std::marker::Unpin
+                    {
+                        Self::#find_by(executor, value, #table).await
+                    }
+                }
+            });
+            methods.push(quote! {
+                /// Row with unique column value or `None`
+                pub async fn #find_by<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, value: #ty, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Option<Row>>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                    Row: std::marker::Unpin
+                {
+                    let table: ::sqlxinsert::TableName = table.into();
+                    let sql = #sql;
+                    Ok(sqlx::query_as::<_,Row>(&sql)
+                        .bind(value)
+                        .fetch_optional(executor)
+                        .await?)
+                }
+
+                #table_method
+            });
+        }
+
+        if field.lookup {
+            let method_name = format!("find_all_by_{}", name);
+            let find_all_by = table_method(attributes, &method_name);
+            let sql = cached_sql(
+                true,
+                quote! { format!("select * from {} where {} = $1 order by {}", table, #column, #key_order) },
+            );
+            let table_method = attributes.table.as_ref().map(|table| {
+                let method = format_ident!("{}", method_name);
+                quote! {
+                    pub async fn #method<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, value: #ty) -> ::sqlxinsert::Result<Vec<Row>>
+                    where
+                        Row: Send,
+                        Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                        Row: std::marker::Unpin
+                    {
+                        Self::#find_all_by(executor, value, #table).await
+                    }
+                }
+            });
+            methods.push(quote! {
+                /// Rows with column value ordered by primary key
+                pub async fn #find_all_by<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, value: #ty, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Vec<Row>>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                    Row: std::marker::Unpin
+                {
+                    let table: ::sqlxinsert::TableName = table.into();
+                    let sql = #sql;
+                    Ok(sqlx::query_as::<_,Row>(&sql)
+                        .bind(value)
+                        .fetch_all(executor)
+                        .await?)
+                }
+
+                #table_method
+            });
+        }
+    }
+
+    methods
+}
+
+/// Owned cursor type of order key: `&str` -> `String`, `&[u8]` -> `Vec<u8>`, other types as declared
+fn cursor_type(ty: &Type) -> TokenStream2 {
+    if let Type::Reference(reference) = ty {
+        match &*reference.elem {
+            Type::Path(path) if path.path.is_ident("str") => return quote! { String },
+            Type::Slice(slice) => {
+                let elem = &slice.elem;
+                return quote! { Vec<#elem> };
+            }
+            _ => {}
+        }
+    }
+    quote! { #ty }
+}
+
+/// `page` ( keyset on `order_key` ) and `page_offset`
+///
+/// One row more than `limit` is selected to find out whether there is a following page. Cursor
+/// is selected as extra `sqlxinsert_cursor` column so rows can be any `sqlx::FromRow` type.
+/// Negative `limit` and `offset` are taken as 0, empty page has no following page.
+fn page_methods(
+    attributes: &ContainerAttributes,
+    db: &TokenStream2,
+    order_key: &Field,
+) -> TokenStream2 {
+    let column = &order_key.column;
+    let cursor_ty = cursor_type(order_key.ty);
+
+    let page_first_sql = cached_sql(
+        true,
+        quote! { format!("select *, {} as sqlxinsert_cursor from {} order by {} limit $1", #column, table, #column) },
+    );
+    let page_after_sql = cached_sql(
+        true,
+        quote! { format!("select *, {} as sqlxinsert_cursor from {} where {} > $1 order by {} limit $2", #column, table, #column, #column) },
+    );
+    let page_offset_sql = cached_sql(
+        true,
+        quote! { format!("select * from {} order by {} limit $1 offset $2", table, #column) },
+    );
+
+    let page = table_method(attributes, "page");
+    let page_offset = table_method(attributes, "page_offset");
+
+    let table_methods = match &attributes.table {
+        Some(table) => quote! {
+            pub async fn page<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, after: Option<#cursor_ty>, limit: i64) -> ::sqlxinsert::Result<::sqlxinsert::Page<Row, #cursor_ty>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                Row: std::marker::Unpin,
+                #cursor_ty: for<'row> sqlx::Decode<'row, #db> + for<'query> sqlx::Encode<'query, #db> + sqlx::Type<#db> + Send
+            {
+                Self::#page(executor, after, limit, #table).await
+            }
+
+            pub async fn page_offset<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, offset: i64, limit: i64) -> ::sqlxinsert::Result<::sqlxinsert::Page<Row, i64>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                Row: std::marker::Unpin
+            {
+                Self::#page_offset(executor, offset, limit, #table).await
+            }
+        },
+        None => quote! {},
+    };
+
+    quote! {
+        /// At most `limit` rows ordered by order key following `after` cursor ( first page for `None` )
+        pub async fn #page<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, after: Option<#cursor_ty>, limit: i64, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<::sqlxinsert::Page<Row, #cursor_ty>>
+        where
+            Row: Send,
+            Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+            Row: std::marker::Unpin,
+            #cursor_ty: for<'row> sqlx::Decode<'row, #db> + for<'query> sqlx::Encode<'query, #db> + sqlx::Type<#db> + Send
+        {
+            let limit = limit.max(0);
+            let table: ::sqlxinsert::TableName = table.into();
+            let rows = match after {
+                Some(after) => {
+                    let sql = #page_after_sql;
+                    sqlx::query(&sql)
+                        .bind(after)
+                        .bind(limit.saturating_add(1))
+                        .fetch_all(executor)
+                        .await?
+                }
+                None => {
+                    let sql = #page_first_sql;
+                    sqlx::query(&sql)
+                        .bind(limit.saturating_add(1))
+                        .fetch_all(executor)
+                        .await?
+                }
+            };
+
+            let mut items = Vec::with_capacity(rows.len());
+            for row in rows.iter().take(limit as usize) {
+                items.push(<Row as sqlx::FromRow<'_, <#db as sqlx::Database>::Row>>::from_row(row)?);
+            }
+            let next = match items.len() {
+                len if len > 0 && rows.len() > len => {
+                    Some(sqlx::Row::try_get::<#cursor_ty, _>(&rows[len - 1], "sqlxinsert_cursor")?)
+                }
+                _ => None,
+            };
+
+            Ok(::sqlxinsert::Page { items, next })
+        }
+
+        /// At most `limit` rows ordered by order key skipping `offset` rows
+        pub async fn #page_offset<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, offset: i64, limit: i64, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<::sqlxinsert::Page<Row, i64>>
+        where
+            Row: Send,
+            Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+            Row: std::marker::Unpin
+        {
+            let offset = offset.max(0);
+            let limit = limit.max(0);
+            let table: ::sqlxinsert::TableName = table.into();
+            let sql = #page_offset_sql;
+            let mut items = sqlx::query_as::<_,Row>(&sql)
+                .bind(limit.saturating_add(1))
+                .bind(offset)
+                .fetch_all(executor)
+                .await?;
+
+            let next = match items.len() as i64 > limit {
+                true if limit > 0 => Some(offset.saturating_add(limit)),
+                _ => None,
+            };
+            items.truncate(limit as usize);
+
+            Ok(::sqlxinsert::Page { items, next })
+        }
+
+        #table_methods
+    }
+}
+
+/// Type of `id` parameter and its values bound in order of key columns
+///
+/// Single key takes its field type, composite key a tuple of all key field types
+/// ( `id: (i32, i32)` bound as `id.0`, `id.1` ).
+fn key_params(keys: &[&Field]) -> (TokenStream2, Vec<TokenStream2>) {
+    if let [key] = keys {
+        let ty = key.ty;
+        return (quote! { #ty }, vec![quote! { id }]);
+    }
+
+    let types = keys.iter().map(|key| key.ty);
+    let values = (0..keys.len())
+        .map(|i| {
+            let index = Index::from(i);
+            quote! { id.#index }
+        })
+        .collect();
+    (quote! { ( #( #types ),* ) }, values)
+}
+
+/// Select, delete, exists and count by `#[sqlxinsert(primary_key)]` for `db` ( `sqlx::Sqlite`, `sqlx::Postgres` )
+///
+/// Also selects by `#[sqlxinsert(unique)]` / `#[sqlxinsert(lookup)]` fields and pages ordered by
+/// `#[sqlxinsert(order_key)]` ( single primary key by default ).
+///
+/// Both databases share `$1` placeholders so statements are the same.
+pub(crate) fn derive(input: DeriveInput, db: TokenStream2) -> syn::Result<TokenStream2> {
+    let attributes = ContainerAttributes::from_ast(&input)?;
+
+    let all_fields = field::fields(&input, &attributes)?;
+    let keys = field::primary_keys(&all_fields);
+    if keys.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "expected a field with `#[sqlxinsert(primary_key)]`",
+        ));
+    }
+    let key_columns: Vec<&str> = keys.iter().map(|key| key.column.as_str()).collect();
+    // "car_id" = $1 and "owner_id" = $2
+    let key_where = query::key_where(&key_columns, 1);
+    let key_order = key_columns.join(", ");
+    let (key_ty, key_values) = key_params(&keys);
+
+    let lookup_methods = lookup_methods(&attributes, &db, &all_fields, &key_order);
+    // Composite keys are not paged without explicit `#[sqlxinsert(order_key)]`
+    let page_methods = match field::order_key(&all_fields)? {
+        Some(order_key) => page_methods(&attributes, &db, order_key),
+        None if keys.len() == 1 => page_methods(&attributes, &db, keys[0]),
+        None => quote! {},
+    };
+
+    let struct_name = &input.ident;
+    let generics = bound_generics(&input, &db);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // select * from "cars" where "car_id" = $1
+    let find_by_id_sql = cached_sql(
+        true,
+        quote! { format!("select * from {} where {}", table, #key_where) },
+    );
+    let find_all_sql = cached_sql(
+        true,
+        quote! { format!("select * from {} order by {}", table, #key_order) },
+    );
+    let delete_by_id_sql = cached_sql(
+        true,
+        quote! { format!("delete from {} where {}", table, #key_where) },
+    );
+    let exists_sql = cached_sql(
+        true,
+        quote! { format!("select exists ( select 1 from {} where {} )", table, #key_where) },
+    );
+    let count_sql = cached_sql(true, quote! { format!("select count(*) from {}", table) });
+
+    let find_by_id = table_method(&attributes, "find_by_id");
+    let find_all = table_method(&attributes, "find_all");
+    let delete_by_id = table_method(&attributes, "delete_by_id");
+    let exists = table_method(&attributes, "exists");
+    let count = table_method(&attributes, "count");
+
+    // Table-less variants when table is known at compile time ( `TABLE` const comes from insert derive )
+    let table_methods = match &attributes.table {
+        Some(table) => quote! {
+            pub async fn find_by_id<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, id: #key_ty) -> ::sqlxinsert::Result<Option<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                Row: std::marker::Unpin
+            {
+                Self::#find_by_id(executor, id, #table).await
+            }
+
+            pub async fn find_all<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>) -> ::sqlxinsert::Result<Vec<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                Row: std::marker::Unpin
+            {
+                Self::#find_all(executor, #table).await
+            }
+
+            pub async fn delete_by_id<'executor>(executor: impl sqlx::Executor<'executor, Database = #db>, id: #key_ty) -> ::sqlxinsert::Result<u64>
+            {
+                Self::#delete_by_id(executor, id, #table).await
+            }
+
+            pub async fn exists<'executor>(executor: impl sqlx::Executor<'executor, Database = #db>, id: #key_ty) -> ::sqlxinsert::Result<bool>
+            {
+                Self::#exists(executor, id, #table).await
+            }
+
+            pub async fn count<'executor>(executor: impl sqlx::Executor<'executor, Database = #db>) -> ::sqlxinsert::Result<i64>
+            {
+                Self::#count(executor, #table).await
+            }
+        },
+        None => quote! {},
+    };
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Row with primary key `id` ( tuple for composite key ) or `None`
+            pub async fn #find_by_id<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, id: #key_ty, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Option<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = #find_by_id_sql;
+                let query = sqlx::query_as::<_,Row>(&sql);
+                #( let query = query.bind(#key_values); )*
+                Ok(query
+                    .fetch_optional(executor)
+                    .await?)
+            }
+
+            /// All rows ordered by primary key
+            pub async fn #find_all<'executor, Row>(executor: impl sqlx::Executor<'executor, Database = #db>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Vec<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, <#db as sqlx::Database>::Row>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = #find_all_sql;
+                Ok(sqlx::query_as::<_,Row>(&sql)
+                    .fetch_all(executor)
+                    .await?)
+            }
+
+            /// Deletes row with primary key `id`, returns number of deleted rows
+            pub async fn #delete_by_id<'executor>(executor: impl sqlx::Executor<'executor, Database = #db>, id: #key_ty, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<u64>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = #delete_by_id_sql;
+                let query = sqlx::query(&sql);
+                #( let query = query.bind(#key_values); )*
+                let res = query
+                    .execute(executor)
+                    .await?;
+                Ok(res.rows_affected())
+            }
+
+            pub async fn #exists<'executor>(executor: impl sqlx::Executor<'executor, Database = #db>, id: #key_ty, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<bool>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = #exists_sql;
+                let query = sqlx::query_scalar::<_,bool>(&sql);
+                #( let query = query.bind(#key_values); )*
+                Ok(query
+                    .fetch_one(executor)
+                    .await?)
+            }
+
+            pub async fn #count<'executor>(executor: impl sqlx::Executor<'executor, Database = #db>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<i64>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = #count_sql;
+                Ok(sqlx::query_scalar::<_,i64>(&sql)
+                    .fetch_one(executor)
+                    .await?)
+            }
+
+            #table_methods
+
+            #( #lookup_methods )*
+
+            #page_methods
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_type_test() {
+        let lookup_type_of =
+            |ty: &str| lookup_type(&syn::parse_str::<Type>(ty).unwrap()).to_string();
+
+        assert_eq!(lookup_type_of("String"), "& str");
+        assert_eq!(lookup_type_of("std::string::String"), "& str");
+        assert_eq!(lookup_type_of("i64"), "i64");
+        assert_eq!(lookup_type_of("&'a str"), "& 'a str");
+        assert_eq!(lookup_type_of("Option<String>"), "Option < String >");
+    }
+
+    #[test]
+    fn cursor_type_test() {
+        let cursor_type_of =
+            |ty: &str| cursor_type(&syn::parse_str::<Type>(ty).unwrap()).to_string();
+
+        assert_eq!(cursor_type_of("&'a str"), "String");
+        assert_eq!(cursor_type_of("&'a [u8]"), "Vec < u8 >");
+        assert_eq!(cursor_type_of("i64"), "i64");
+    }
+}
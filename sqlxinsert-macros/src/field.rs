@@ -0,0 +1,351 @@
+use syn::{ext::IdentExt, Data, DataStruct, DeriveInput, Error, Fields, Ident, Result, Type};
+#[cfg(feature = "postgres")]
+use syn::{GenericArgument, PathArguments};
+
+use crate::attr::{ContainerAttributes, FieldAttributes};
+
+/// Struct field inserted into database column
+pub(crate) struct Field<'a> {
+    pub ident: &'a Ident,
+    #[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+    pub ty: &'a Type,
+    /// Column name as written into sql query
+    #[cfg_attr(
+        not(any(feature = "sqlite", feature = "postgres", feature = "any")),
+        allow(dead_code)
+    )]
+    pub column: String,
+    /// Column name without quotes
+    #[cfg(any(feature = "mysql", feature = "any"))]
+    pub name: String,
+    /// Omitted from insert so database default applies
+    pub default: bool,
+    /// `Option` field inserted only when `Some`
+    pub skip_none: bool,
+    /// Identifies row in update `where` clause
+    pub primary_key: bool,
+    /// Conflict target of upsert
+    #[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+    pub conflict_key: bool,
+    /// Single row lookup `find_by_<field>`
+    #[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+    pub unique: bool,
+    /// All rows lookup `find_all_by_<field>`
+    #[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+    pub lookup: bool,
+    /// Keyset pagination order
+    #[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+    pub order_key: bool,
+    /// Optimistic locking version checked and incremented by update
+    #[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+    pub version: bool,
+    /// Postgres type name used for array casts ( `int4` -> `$1::int4[]` )
+    #[cfg(feature = "postgres")]
+    pub pg_type: Option<String>,
+}
+
+/// struct Car { car_id: i32, #[sqlxinsert(rename = "name")] car_name: String }
+/// -> [ "car_id", "name" ]
+///
+/// Fields with `#[sqlxinsert(skip)]` are not columns and are left out.
+pub(crate) fn fields<'a>(
+    input: &'a DeriveInput,
+    attributes: &ContainerAttributes,
+) -> Result<Vec<Field<'a>>> {
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        Data::Struct(DataStruct {
+            fields: Fields::Unit,
+            ..
+        }) => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "expected a struct with named fields",
+            ))
+        }
+        Data::Struct(DataStruct { fields, .. }) => {
+            return Err(Error::new_spanned(
+                fields,
+                "expected a struct with named fields",
+            ))
+        }
+        Data::Enum(data) => {
+            return Err(Error::new_spanned(
+                data.enum_token,
+                "expected a struct with named fields",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(Error::new_spanned(
+                data.union_token,
+                "expected a struct with named fields",
+            ))
+        }
+    };
+
+    let mut columns = Vec::new();
+    for field in fields {
+        let field_attributes = FieldAttributes::from_ast(field)?;
+        if field_attributes.skip {
+            continue;
+        }
+
+        let ident = field.ident.as_ref().expect("named field");
+        // r#type -> type
+        let name = ident.unraw().to_string();
+
+        let skip_none = match (field_attributes.skip_none, is_option(&field.ty)) {
+            (true, false) => {
+                return Err(Error::new_spanned(
+                    &field.ty,
+                    format!("skip_none is allowed only on `Option` field `{}`", name),
+                ))
+            }
+            (true, true) => true,
+            (false, is_option) => attributes.skip_none && is_option,
+        };
+
+        // Version is always inserted and matched by value
+        if field_attributes.version
+            && (field_attributes.primary_key || field_attributes.default || is_option(&field.ty))
+        {
+            return Err(Error::new_spanned(
+                ident,
+                format!(
+                    "version is not allowed on primary_key, default or `Option` field `{}`",
+                    name
+                ),
+            ));
+        }
+        // Incremented by `version + 1` in update
+        if field_attributes.version && !is_integer(&field.ty) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                format!(
+                    "version is allowed only on `i16`, `i32` or `i64` field `{}`",
+                    name
+                ),
+            ));
+        }
+
+        let name = match (field_attributes.rename, &attributes.rename_all) {
+            (Some(rename), _) => rename,
+            (None, Some(rename_all)) => rename_all.apply(&name),
+            (None, None) => name,
+        };
+        // Columns are quoted so reserved words and letter case are kept as written
+        let column = quote_identifier(&name);
+
+        columns.push(Field {
+            ident,
+            ty: &field.ty,
+            column,
+            #[cfg(any(feature = "mysql", feature = "any"))]
+            name,
+            default: field_attributes.default,
+            skip_none,
+            primary_key: field_attributes.primary_key,
+            conflict_key: field_attributes.conflict_key,
+            unique: field_attributes.unique,
+            lookup: field_attributes.lookup,
+            order_key: field_attributes.order_key,
+            version: field_attributes.version,
+            #[cfg(feature = "postgres")]
+            pg_type: field_attributes.pg_type.or_else(|| pg_type(&field.ty)),
+        });
+    }
+
+    Ok(columns)
+}
+
+/// Fields bound into insert query ( without `#[sqlxinsert(default)]` )
+pub(crate) fn insert_fields<'a, 'b>(fields: &'b [Field<'a>]) -> Vec<&'b Field<'a>> {
+    fields.iter().filter(|field| !field.default).collect()
+}
+
+/// Fields set by update ( without `#[sqlxinsert(primary_key)]` and `#[sqlxinsert(version)]` )
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn update_fields<'a, 'b>(fields: &'b [Field<'a>]) -> Vec<&'b Field<'a>> {
+    fields
+        .iter()
+        .filter(|field| !field.primary_key && !field.version)
+        .collect()
+}
+
+/// Fields with `#[sqlxinsert(primary_key)]` ( more fields for composite key )
+pub(crate) fn primary_keys<'a, 'b>(fields: &'b [Field<'a>]) -> Vec<&'b Field<'a>> {
+    fields.iter().filter(|field| field.primary_key).collect()
+}
+
+/// Field with `#[sqlxinsert(order_key)]`
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn order_key<'a, 'b>(fields: &'b [Field<'a>]) -> Result<Option<&'b Field<'a>>> {
+    let mut keys = fields.iter().filter(|field| field.order_key);
+    let key = keys.next();
+    if let Some(other) = keys.next() {
+        return Err(Error::new_spanned(
+            other.ident,
+            "only one order_key field is supported",
+        ));
+    }
+    Ok(key)
+}
+
+/// Field with `#[sqlxinsert(version)]`
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn version<'a, 'b>(fields: &'b [Field<'a>]) -> Result<Option<&'b Field<'a>>> {
+    let mut versions = fields.iter().filter(|field| field.version);
+    let version = versions.next();
+    if let Some(other) = versions.next() {
+        return Err(Error::new_spanned(
+            other.ident,
+            "only one version field is supported",
+        ));
+    }
+    Ok(version)
+}
+
+/// Conflict target of upsert: `#[sqlxinsert(conflict_key)]` fields or primary key
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn conflict_keys<'a, 'b>(fields: &'b [Field<'a>]) -> Vec<&'b Field<'a>> {
+    let keys: Vec<&Field> = fields.iter().filter(|field| field.conflict_key).collect();
+    if keys.is_empty() {
+        primary_keys(fields)
+    } else {
+        keys
+    }
+}
+
+/// Option<String> -> true
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident == "Option")
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+/// i16, i32, i64 -> true
+fn is_integer(ty: &Type) -> bool {
+    match ty {
+        Type::Path(type_path) => {
+            type_path.qself.is_none()
+                && ["i16", "i32", "i64"]
+                    .iter()
+                    .any(|integer| type_path.path.is_ident(integer))
+        }
+        _ => false,
+    }
+}
+
+/// Postgres type of common Rust types
+///
+/// i32 -> int4, Option<String> -> text, other types are left to sqlx
+#[cfg(feature = "postgres")]
+fn pg_type(ty: &Type) -> Option<String> {
+    let segment = match ty {
+        Type::Path(type_path) => type_path.path.segments.last()?,
+        Type::Reference(reference) => return pg_type(&reference.elem),
+        _ => return None,
+    };
+
+    if segment.ident == "Option" {
+        return match &segment.arguments {
+            PathArguments::AngleBracketed(args) => match args.args.first()? {
+                GenericArgument::Type(inner) => pg_type(inner),
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    let pg_type = match segment.ident.to_string().as_str() {
+        "bool" => "bool",
+        "i16" => "int2",
+        "i32" => "int4",
+        "i64" => "int8",
+        "f32" => "float4",
+        "f64" => "float8",
+        "String" | "str" => "text",
+        _ => return None,
+    };
+    Some(pg_type.to_string())
+}
+
+/// order -> "order"
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// order -> `order` ( MySQL )
+#[cfg(any(feature = "mysql", feature = "any"))]
+pub(crate) fn backtick_identifier(name: &str) -> String {
+    format!("`{}`", name.replace('`', "``"))
+}
+
+/// cars -> "cars", analytics.events -> "analytics"."events" ( as `sqlxinsert::TableName` )
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn quote_table(table: &str) -> String {
+    table
+        .splitn(2, '.')
+        .map(quote_identifier)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// analytics.events -> `analytics`.`events` ( MySQL )
+#[cfg(feature = "mysql")]
+pub(crate) fn backtick_table(table: &str) -> String {
+    table
+        .splitn(2, '.')
+        .map(backtick_identifier)
+        .collect::<Vec<String>>()
+        .join(".")
+}
+
+/// [ car_id, car_name ] -> "car_id", "car_name"
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn columns(fields: &[&Field]) -> String {
+    fields
+        .iter()
+        .map(|field| field.column.as_str())
+        .collect::<Vec<&str>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "postgres")]
+    fn pg_type_test() {
+        let pg_type_of = |ty: &str| pg_type(&syn::parse_str::<Type>(ty).unwrap());
+
+        assert_eq!(pg_type_of("i32").as_deref(), Some("int4"));
+        assert_eq!(pg_type_of("Option<String>").as_deref(), Some("text"));
+        assert_eq!(pg_type_of("&'a str").as_deref(), Some("text"));
+        assert_eq!(
+            pg_type_of("std::option::Option<f64>").as_deref(),
+            Some("float8")
+        );
+        assert_eq!(pg_type_of("uuid::Uuid"), None);
+    }
+
+    #[test]
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    fn quote_table_test() {
+        assert_eq!(quote_table("cars"), r#""cars""#);
+        assert_eq!(quote_table("a.b.c"), r#""a"."b.c""#);
+        assert_eq!(quote_table(r#"order"s"#), r#""order""s""#);
+        #[cfg(feature = "mysql")]
+        assert_eq!(backtick_table("analytics.events"), "`analytics`.`events`");
+    }
+}
@@ -0,0 +1,237 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attr::ContainerAttributes;
+use crate::field::{self, backtick_identifier, Field};
+use crate::{
+    bound_generics, cached_sql, insertable_impl, query, reselect_keys, table_const, table_method,
+};
+
+/// 2 -> ?,?
+fn question_values(max: usize) -> String {
+    vec!["?"; max].join(",")
+}
+
+/// [ car_id, car_name ] -> "`car_id`, `car_name`"
+fn columns(fields: &[&Field]) -> String {
+    fields
+        .iter()
+        .map(|field| backtick_identifier(&field.name))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// ( `car_id`, `car_name` ) values ( ?,? ) when known at compile time
+fn static_insert_values(fields: &[&Field]) -> Option<String> {
+    if fields.iter().any(|field| field.skip_none) {
+        return None;
+    }
+    Some(format!(
+        "( {} ) values ( {} )",
+        columns(fields),
+        question_values(fields.len())
+    ))
+}
+
+/// ( `car_id`, `car_name` ) values ( ?,? ) as expression
+///
+/// Same as `query::insert_values_expr` with MySQL placeholders and quoting.
+fn insert_values_expr(fields: &[&Field]) -> TokenStream2 {
+    if let Some(insert_values) = static_insert_values(fields) {
+        return quote! { #insert_values };
+    }
+
+    let pushes = fields.iter().map(|field| {
+        let ident = field.ident;
+        let column = backtick_identifier(&field.name);
+        if field.skip_none {
+            quote! {
+                if self.#ident.is_some() {
+                    columns.push(#column);
+                }
+            }
+        } else {
+            quote! { columns.push(#column); }
+        }
+    });
+
+    quote! {
+        {
+            let mut columns: Vec<&str> = Vec::new();
+            #( #pushes )*
+            format!("( {} ) values ( {} )", columns.join(", "), vec!["?"; columns.len()].join(","))
+        }
+    }
+}
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attributes = ContainerAttributes::from_ast(&input)?;
+
+    let all_fields = field::fields(&input, &attributes)?;
+    let fields = field::insert_fields(&all_fields);
+    let binds = query::bind_fields(&fields);
+
+    let struct_name = &input.ident;
+    let generics = bound_generics(&input, &quote! { sqlx::MySql });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // ( `name`, `color` ) values ( ?,? )
+    let insert_values = insert_values_expr(&fields);
+
+    // Inserted row is selected by primary key value or by `last_insert_id()` of AUTO_INCREMENT key
+    let (key_where, key_values) = reselect_keys(&all_fields);
+
+    let static_insert_values = static_insert_values(&fields);
+    let insert_sql = cached_sql(
+        static_insert_values.is_some(),
+        quote! { format!("insert into {} {}", table.backtick_quoted(), #insert_values) },
+    );
+
+    let table_const = table_const(&attributes);
+    let insert_query = table_method(&attributes, "insert_query");
+    let insert_raw = table_method(&attributes, "insert_raw");
+    let insert = table_method(&attributes, "insert");
+
+    // Statement precomputed when both table and columns are known at compile time
+    let static_sql = match (&attributes.table, &static_insert_values) {
+        (Some(table), Some(insert_values)) => {
+            let sql = format!(
+                "insert into {} {}",
+                field::backtick_table(table),
+                insert_values
+            );
+            Some(quote! {
+                /// Insert statement of `TABLE`
+                pub const INSERT_SQL: &'static str = #sql;
+            })
+        }
+        _ => None,
+    };
+
+    let table_insert_methods = match &static_sql {
+        Some(_) => quote! {
+            pub fn insert_query(&self) -> std::borrow::Cow<'static, str>
+            {
+                std::borrow::Cow::Borrowed(Self::INSERT_SQL)
+            }
+
+            pub async fn insert_raw<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::MySql>) -> ::sqlxinsert::Result<sqlx::mysql::MySqlQueryResult>
+            {
+                let query = sqlx::query(Self::INSERT_SQL);
+                #( #binds )*
+                Ok(query.execute(executor).await?)
+            }
+        },
+        None => quote! {
+            pub fn insert_query(&self) -> std::borrow::Cow<'static, str>
+            {
+                self.#insert_query(Self::TABLE)
+            }
+
+            pub async fn insert_raw<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::MySql>) -> ::sqlxinsert::Result<sqlx::mysql::MySqlQueryResult>
+            {
+                self.#insert_raw(executor, Self::TABLE).await
+            }
+        },
+    };
+
+    // Table-less variants when table is known at compile time
+    let table_methods = match &attributes.table {
+        Some(_) => quote! {
+            #table_insert_methods
+
+            pub async fn insert<'acquire, Row>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::MySql>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::mysql::MySqlRow>,
+                Row: std::marker::Unpin
+            {
+                self.#insert(conn, Self::TABLE).await
+            }
+        },
+        None => quote! {},
+    };
+
+    // Insert and select of inserted row with `conn` and `table` in scope ( shared with `Insertable` )
+    let insert_body = quote! {
+        let mut conn = conn.acquire().await?;
+        let sql = #insert_sql;
+        let query = sqlx::query(&sql);
+        #( #binds )*
+        let res = query.execute(&mut *conn).await?;
+
+        let sql = format!("select * from {} where {}", table.backtick_quoted(), #key_where);
+        let query = sqlx::query_as::<_,Row>(&sql);
+        #( let query = query.bind(#key_values); )*
+        let res: Row = query
+            .fetch_one(&mut *conn)
+            .await?;
+
+        Ok(res)
+    };
+
+    let insertable = insertable_impl(
+        &input,
+        &attributes,
+        quote! { sqlx::MySql },
+        fields
+            .iter()
+            .map(|field| backtick_identifier(&field.name))
+            .collect(),
+        &binds,
+        insert_body.clone(),
+    );
+    if attributes.trait_only {
+        return Ok(insertable);
+    }
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #table_const
+
+            #static_sql
+
+            pub fn #insert_query(&self, table: impl Into<::sqlxinsert::TableName>) -> std::borrow::Cow<'static, str>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                #insert_sql
+            }
+
+            pub async fn #insert_raw<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::MySql>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::mysql::MySqlQueryResult>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#insert_query(&table);
+                let query = sqlx::query(&sql);
+                #( #binds )*
+                Ok(query.execute(executor).await?)
+            }
+
+            /// Inserted row is selected again by primary key ( `last_insert_id()` for AUTO_INCREMENT )
+            pub async fn #insert<'acquire, Row>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::MySql>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::mysql::MySqlRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                #insert_body
+            }
+
+            #table_methods
+        }
+
+        #insertable
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn question_values_test() {
+        assert_eq!(question_values(3), "?,?,?");
+        assert_eq!(backtick_identifier("order"), "`order`");
+    }
+}
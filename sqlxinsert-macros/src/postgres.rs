@@ -0,0 +1,531 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attr::ContainerAttributes;
+use crate::field;
+use crate::{bound_generics, cached_sql, insertable_impl, query, table_const, table_method};
+
+/// Maximum bind parameters in one query
+const MAX_BINDS: usize = 65535;
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attributes = ContainerAttributes::from_ast(&input)?;
+
+    let all_fields = field::fields(&input, &attributes)?;
+    let fields = field::insert_fields(&all_fields);
+    let binds = query::bind_fields(&fields);
+
+    // struct Car ...
+    // -> Car
+    let struct_name = &input.ident;
+    let generics = bound_generics(&input, &quote! { sqlx::Postgres });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // struct { id: i32, name: String }
+    // -> ( id, name ) values ( $1,$2 )
+    let insert_values = query::insert_values_expr(&fields);
+
+    let static_insert_values = query::static_insert_values(&fields);
+    let insert_sql = cached_sql(
+        static_insert_values.is_some(),
+        quote! { format!("insert into {} {} returning *", table, #insert_values) },
+    );
+
+    let table_const = table_const(&attributes);
+    let insert_query = table_method(&attributes, "insert_query");
+    let insert = table_method(&attributes, "insert");
+
+    // Statement precomputed when both table and columns are known at compile time
+    let static_sql = match (&attributes.table, &static_insert_values) {
+        (Some(table), Some(insert_values)) => {
+            let sql = format!(
+                "insert into {} {} returning *",
+                field::quote_table(table),
+                insert_values
+            );
+            Some(quote! {
+                /// Insert statement of `TABLE` returning inserted row
+                pub const INSERT_SQL: &'static str = #sql;
+            })
+        }
+        _ => None,
+    };
+
+    // Table-less variants when table is known at compile time
+    let table_methods = match (&attributes.table, &static_sql) {
+        (Some(_), Some(_)) => quote! {
+            pub async fn insert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin
+            {
+                let query = sqlx::query_as::<_,Row>(Self::INSERT_SQL);
+                #( #binds )*
+                Ok(query.fetch_one(executor).await?)
+            }
+        },
+        (Some(_), None) => quote! {
+            pub async fn insert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin
+            {
+                self.#insert(executor, Self::TABLE).await
+            }
+        },
+        (None, _) => quote! {},
+    };
+
+    // Optimistic locking by `#[sqlxinsert(version)]` in update and upsert
+    let version = field::version(&all_fields)?;
+    // No returned row with version column means the row was changed since it was read
+    let fetch_updated = match version {
+        Some(_) => quote! {
+            query
+                .fetch_optional(executor)
+                .await?
+                .ok_or(::sqlxinsert::InsertError::StaleObject)?
+        },
+        None => quote! {
+            query
+                .fetch_one(executor)
+                .await?
+        },
+    };
+
+    // Update by `#[sqlxinsert(primary_key)]` returning updated row
+    let primary_keys = field::primary_keys(&all_fields);
+    let update_methods = match primary_keys.as_slice() {
+        [] => quote! {},
+        keys => {
+            let update_fields = field::update_fields(&all_fields);
+            let update_binds = query::bind_fields(&update_fields);
+            // set name = $1 where id = $2 ( and owner_id = $3 )
+            let update_set = query::update_set_expr(&update_fields, keys, version);
+            let key_names = keys.iter().map(|key| key.ident);
+            let version_bind = version.map(|version| {
+                let version_name = version.ident;
+                quote! { let query = query.bind(&self.#version_name); }
+            });
+
+            let update_query = table_method(&attributes, "update_query");
+            let update = table_method(&attributes, "update");
+
+            let update_table_methods = match &attributes.table {
+                Some(_) => quote! {
+                    pub async fn update<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Row>
+                    where
+                        Row: Send,
+                        Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                        Row: std::marker::Unpin
+                    {
+                        self.#update(executor, Self::TABLE).await
+                    }
+                },
+                None => quote! {},
+            };
+
+            quote! {
+                fn #update_query(&self, table: impl Into<::sqlxinsert::TableName>) -> String
+                {
+                    let table: ::sqlxinsert::TableName = table.into();
+                    format!("update {} {} returning *", table, #update_set)
+                }
+
+                pub async fn #update<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                    Row: std::marker::Unpin
+                {
+                    let table: ::sqlxinsert::TableName = table.into();
+                    let sql = self.#update_query(&table);
+
+                    let query = sqlx::query_as::<_,Row>(&sql);
+                    #( #update_binds )*
+                    #( let query = query.bind(&self.#key_names); )*
+                    #version_bind
+                    let res: Row = #fetch_updated;
+
+                    Ok(res)
+                }
+
+                #update_table_methods
+            }
+        }
+    };
+
+    // Upsert on `#[sqlxinsert(conflict_key)]` ( or primary key )
+    let conflict_keys = field::conflict_keys(&all_fields);
+    let upsert_methods = if conflict_keys.is_empty() {
+        quote! {}
+    } else {
+        // ( id, name ) values ( $1,$2 ) on conflict ( id ) do update set name = excluded.name
+        let upsert_values = query::upsert_values_expr(&fields, &conflict_keys, version);
+        let conflict_columns = field::columns(&conflict_keys);
+
+        let upsert_query = table_method(&attributes, "upsert_query");
+        let upsert = table_method(&attributes, "upsert");
+        let insert_or_ignore_query = table_method(&attributes, "insert_or_ignore_query");
+        let insert_or_ignore = table_method(&attributes, "insert_or_ignore");
+
+        let upsert_table_methods = match &attributes.table {
+            Some(_) => quote! {
+                pub async fn upsert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Row>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                    Row: std::marker::Unpin
+                {
+                    self.#upsert(executor, Self::TABLE).await
+                }
+
+                pub async fn insert_or_ignore<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Option<Row>>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                    Row: std::marker::Unpin
+                {
+                    self.#insert_or_ignore(executor, Self::TABLE).await
+                }
+            },
+            None => quote! {},
+        };
+
+        quote! {
+            fn #upsert_query(&self, table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} {} returning *", table, #upsert_values)
+            }
+
+            fn #insert_or_ignore_query(&self, table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} {} on conflict ( {} ) do nothing returning *", table, #insert_values, #conflict_columns)
+            }
+
+            pub async fn #upsert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#upsert_query(&table);
+
+                let query = sqlx::query_as::<_,Row>(&sql);
+                #( #binds )*
+                let res: Row = #fetch_updated;
+
+                Ok(res)
+            }
+
+            /// `None` when row already exists
+            pub async fn #insert_or_ignore<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Option<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#insert_or_ignore_query(&table);
+
+                let query = sqlx::query_as::<_,Row>(&sql);
+                #( #binds )*
+                let res: Option<Row> = query
+                    .fetch_optional(executor)
+                    .await?;
+
+                Ok(res)
+            }
+
+            #upsert_table_methods
+        }
+    };
+
+    // Multi-row insert returning all rows, not possible without columns
+    let insert_many_methods = if fields.is_empty() {
+        quote! {}
+    } else {
+        let columns = field::columns(&fields);
+        // ( $1,$2 ),( $3,$4 )
+        let insert_many_values = query::insert_many_values_expr(&fields);
+        let rows_per_insert = query::rows_per_insert(MAX_BINDS, &fields);
+        let field_name = fields.iter().map(|field| field.ident);
+
+        let insert_many_query = table_method(&attributes, "insert_many_query");
+        let insert_many = table_method(&attributes, "insert_many");
+
+        let insert_many_table_methods = match &attributes.table {
+            Some(_) => quote! {
+                pub async fn insert_many<'acquire, Row>(rows: &[Self], conn: impl sqlx::Acquire<'acquire, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Vec<Row>>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                    Row: std::marker::Unpin
+                {
+                    Self::#insert_many(rows, conn, Self::TABLE).await
+                }
+            },
+            None => quote! {},
+        };
+
+        quote! {
+            fn #insert_many_query(table: impl Into<::sqlxinsert::TableName>, rows: usize) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} ( {} ) values {} returning *", table, #columns, #insert_many_values)
+            }
+
+            /// Rows are inserted in chunks so bind parameters stay under Postgres limit
+            ///
+            /// `skip_none` is not applied, `None` is inserted as `NULL`
+            pub async fn #insert_many<'acquire, Row>(rows: &[Self], conn: impl sqlx::Acquire<'acquire, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Vec<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let mut tx = conn.begin().await?;
+                let mut res = Vec::with_capacity(rows.len());
+                for chunk in rows.chunks(#rows_per_insert) {
+                    let sql = Self::#insert_many_query(&table, chunk.len());
+                    let mut query = sqlx::query_as::<_,Row>(&sql);
+                    for row in chunk {
+                        #( query = query.bind(&row.#field_name); )*
+                    }
+                    res.extend(query.fetch_all(&mut tx).await?);
+                }
+                tx.commit().await?;
+                Ok(res)
+            }
+
+            #insert_many_table_methods
+        }
+    };
+
+    // Bulk insert binding one array per column with `#[sqlxinsert(unnest)]`, not possible without columns
+    let insert_unnest_methods = if !attributes.unnest || fields.is_empty() {
+        quote! {}
+    } else {
+        let columns = field::columns(&fields);
+        // unnest( $1::text[], $2::int4[] )
+        let unnest_values = query::unnest_values(&fields);
+        let field_name = fields.iter().map(|field| field.ident);
+
+        // Arrays of generic field types have to be bound explicitly
+        let array_bounds = match input.generics.type_params().next() {
+            Some(_) => {
+                let ty = fields.iter().map(|field| field.ty);
+                quote! {
+                    #(
+                        #ty: Clone,
+                        Vec<#ty>: for<'query> sqlx::Encode<'query, sqlx::Postgres> + sqlx::Type<sqlx::Postgres>,
+                    )*
+                }
+            }
+            None => quote! {},
+        };
+
+        let insert_unnest_query = table_method(&attributes, "insert_unnest_query");
+        let insert_unnest = table_method(&attributes, "insert_unnest");
+
+        let insert_unnest_table_methods = match &attributes.table {
+            Some(_) => quote! {
+                pub async fn insert_unnest<'executor, Row>(rows: &[Self], executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<Vec<Row>>
+                where
+                    Row: Send,
+                    Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                    Row: std::marker::Unpin,
+                    #array_bounds
+                {
+                    Self::#insert_unnest(rows, executor, Self::TABLE).await
+                }
+            },
+            None => quote! {},
+        };
+
+        quote! {
+            fn #insert_unnest_query(table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} ( {} ) select * from {} returning *", table, #columns, #unnest_values)
+            }
+
+            /// Any number of rows is inserted with single statement binding one array per column
+            ///
+            /// Field types have to be `Clone`, `skip_none` is not applied
+            pub async fn #insert_unnest<'executor, Row>(rows: &[Self], executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Vec<Row>>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin,
+                #array_bounds
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = Self::#insert_unnest_query(&table);
+
+                let query = sqlx::query_as::<_,Row>(&sql);
+                #(
+                    let query = query.bind(rows.iter().map(|row| row.#field_name.clone()).collect::<Vec<_>>());
+                )*
+                let res: Vec<Row> = query
+                    .fetch_all(executor)
+                    .await?;
+
+                Ok(res)
+            }
+
+            #insert_unnest_table_methods
+        }
+    };
+
+    // COPY in binary format, values are encoded by sqlx same as binds, not possible without columns
+    let copy_in_methods = if fields.is_empty() {
+        quote! {}
+    } else {
+        let columns = field::columns(&fields);
+        let field_count = fields.len() as i16;
+        let field_name = fields.iter().map(|field| field.ident);
+
+        let copy_in_query = table_method(&attributes, "copy_in_query");
+        let copy_in = table_method(&attributes, "copy_in");
+
+        let copy_in_table_methods = match &attributes.table {
+            Some(_) => quote! {
+                pub async fn copy_in<'row, 'acquire, I>(rows: I, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Postgres>) -> ::sqlxinsert::Result<u64>
+                where
+                    I: IntoIterator<Item = &'row Self>,
+                    Self: 'row
+                {
+                    Self::#copy_in(rows, conn, Self::TABLE).await
+                }
+            },
+            None => quote! {},
+        };
+
+        quote! {
+            fn #copy_in_query(table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("copy {} ( {} ) from stdin with ( format binary )", table, #columns)
+            }
+
+            /// Rows are sent with `COPY FROM STDIN` in binary format, returns number of copied rows
+            ///
+            /// Field types have to match column types exactly ( `i32` -> `INTEGER` ), `skip_none` is not applied
+            pub async fn #copy_in<'row, 'acquire, I>(rows: I, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<u64>
+            where
+                I: IntoIterator<Item = &'row Self>,
+                Self: 'row
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = Self::#copy_in_query(&table);
+                let mut conn = conn.acquire().await?;
+                let mut copy = conn.copy_in_raw(&sql).await?;
+
+                // Header: signature, flags, header extension length
+                let mut buf: Vec<u8> = Vec::new();
+                buf.extend_from_slice(b"PGCOPY\n\xff\r\n\0");
+                buf.extend_from_slice(&0i32.to_be_bytes());
+                buf.extend_from_slice(&0i32.to_be_bytes());
+
+                for row in rows {
+                    buf.extend_from_slice(&#field_count.to_be_bytes());
+                    #(
+                        let mut value = sqlx::postgres::PgArgumentBuffer::default();
+                        match sqlx::Encode::<sqlx::Postgres>::encode_by_ref(&row.#field_name, &mut value) {
+                            sqlx::encode::IsNull::Yes => buf.extend_from_slice(&(-1i32).to_be_bytes()),
+                            sqlx::encode::IsNull::No => {
+                                buf.extend_from_slice(&(value.len() as i32).to_be_bytes());
+                                buf.extend_from_slice(&value);
+                            }
+                        }
+                    )*
+
+                    if buf.len() >= 64 * 1024 {
+                        copy.send(buf.as_slice()).await?;
+                        buf.clear();
+                    }
+                }
+
+                // Trailer
+                buf.extend_from_slice(&(-1i16).to_be_bytes());
+                copy.send(buf.as_slice()).await?;
+
+                Ok(copy.finish().await?)
+            }
+
+            #copy_in_table_methods
+        }
+    };
+
+    let insertable = insertable_impl(
+        &input,
+        &attributes,
+        quote! { sqlx::Postgres },
+        fields.iter().map(|field| field.column.clone()).collect(),
+        &binds,
+        quote! {
+            let sql = #insert_sql;
+            let mut conn = conn.acquire().await?;
+            let query = sqlx::query_as::<_,Row>(&sql);
+            #( #binds )*
+            Ok(query.fetch_one(&mut *conn).await?)
+        },
+    );
+    if attributes.trait_only {
+        return Ok(insertable);
+    }
+
+    Ok(quote! {
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #table_const
+
+            #static_sql
+
+            fn #insert_query(&self, table: impl Into<::sqlxinsert::TableName>) -> std::borrow::Cow<'static, str>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                #insert_sql
+            }
+
+            pub async fn #insert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Postgres>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::postgres::PgRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#insert_query(&table);
+
+                let query = sqlx::query_as::<_,Row>(&sql);
+                #( #binds )*
+                let res: Row = query
+                    .fetch_one(executor)
+                    .await?;
+
+                Ok(res)
+            }
+
+            #table_methods
+
+            #update_methods
+
+            #upsert_methods
+
+            #insert_many_methods
+
+            #insert_unnest_methods
+
+            #copy_in_methods
+        }
+
+        #insertable
+    })
+}
@@ -0,0 +1,411 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+use crate::dollar_values;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+use crate::field;
+use crate::field::Field;
+
+/// ( car_id, car_name ) values ( $1,$2 )
+///
+/// All columns filled by database -> default values
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn insert_values(columns: &str, values: &str) -> String {
+    if columns.is_empty() {
+        "default values".to_string()
+    } else {
+        format!("( {} ) values ( {} )", columns, values)
+    }
+}
+
+/// ( car_id, car_name ) values ( $1,$2 ) when known at compile time
+///
+/// `None` with `#[sqlxinsert(skip_none)]` field as columns depend on field values.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn static_insert_values(fields: &[&Field]) -> Option<String> {
+    if fields.iter().any(|field| field.skip_none) {
+        return None;
+    }
+    let columns = field::columns(fields);
+    let values = dollar_values(fields.len());
+    Some(insert_values(&columns, &values))
+}
+
+/// ( car_id, car_name ) values ( $1,$2 ) as expression
+///
+/// Known at compile time unless there is `#[sqlxinsert(skip_none)]` field, then columns
+/// are collected at runtime from fields which are `Some`.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn insert_values_expr(fields: &[&Field]) -> TokenStream2 {
+    if let Some(insert_values) = static_insert_values(fields) {
+        return quote! { #insert_values };
+    }
+
+    let pushes = column_pushes(fields);
+
+    quote! {
+        {
+            let mut columns: Vec<&str> = Vec::new();
+            #( #pushes )*
+            let values = (1..columns.len() + 1)
+                .map(|s| format!("${}", s))
+                .collect::<Vec<String>>()
+                .join(",");
+            if columns.is_empty() {
+                "default values".to_string()
+            } else {
+                format!("( {} ) values ( {} )", columns.join(", "), values)
+            }
+        }
+    }
+}
+
+/// ( $1,$2 ),( $3,$4 ) for `rows` in scope as expression
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn insert_many_values_expr(fields: &[&Field]) -> TokenStream2 {
+    let count = fields.len();
+    quote! {
+        (0..rows)
+            .map(|row| {
+                let values = (1..#count + 1)
+                    .map(|s| format!("${}", row * #count + s))
+                    .collect::<Vec<String>>()
+                    .join(",");
+                format!("( {} )", values)
+            })
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
+
+/// unnest( $1::text[], $2::int4[] )
+///
+/// Cast is left out for fields without known Postgres type, sqlx sends array type anyway.
+#[cfg(feature = "postgres")]
+pub(crate) fn unnest_values(fields: &[&Field]) -> String {
+    let arrays = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.pg_type {
+            Some(pg_type) => format!("${}::{}[]", i + 1, pg_type),
+            None => format!("${}", i + 1),
+        })
+        .collect::<Vec<String>>()
+        .join(", ");
+    format!("unnest( {} )", arrays)
+}
+
+/// Rows in one multi-row insert so that bind parameters stay under `max_binds`
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn rows_per_insert(max_binds: usize, fields: &[&Field]) -> usize {
+    max_binds / fields.len().max(1)
+}
+
+/// on conflict ( car_id ) do update set car_name = excluded.car_name
+///
+/// Without columns to set first key is assigned to itself so the row is still returned.
+/// `version` column is not set from inserted row ( see `upsert_version_expr` ).
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn on_conflict_update(columns: &[&str], keys: &[&str], version: Option<&str>) -> String {
+    let set = columns
+        .iter()
+        .filter(|column| !keys.contains(column) && Some(**column) != version)
+        .map(|column| format!("{} = excluded.{}", column, column))
+        .collect::<Vec<String>>();
+    let set = if set.is_empty() {
+        format!("{} = excluded.{}", keys[0], keys[0])
+    } else {
+        set.join(", ")
+    };
+    format!("on conflict ( {} ) do update set {}", keys.join(", "), set)
+}
+
+/// , version = "cars".version + 1 where "cars".version = excluded.version with `table` in scope
+///
+/// Existing row is updated only when its version matches inserted one so stale row does not
+/// overwrite newer one. Column is qualified by table as plain `version` is ambiguous in Postgres.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn upsert_version_expr(version: &str) -> TokenStream2 {
+    quote! {
+        format!(
+            ", {} = {}.{} + 1 where {}.{} = excluded.{}",
+            #version, table, #version, table, #version, #version
+        )
+    }
+}
+
+/// ( car_id, car_name ) values ( $1,$2 ) on conflict ( car_id ) do update set ... as expression
+///
+/// With `#[sqlxinsert(skip_none)]` fields only columns which are inserted are updated.
+/// With `#[sqlxinsert(version)]` field expression uses `table` in scope.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn upsert_values_expr(
+    fields: &[&Field],
+    keys: &[&Field],
+    version: Option<&Field>,
+) -> TokenStream2 {
+    let keys: Vec<&str> = keys.iter().map(|key| key.column.as_str()).collect();
+    let version = version.map(|version| version.column.as_str());
+    let version_set = match version {
+        Some(version) => upsert_version_expr(version),
+        None => quote! { "" },
+    };
+    if !fields.iter().any(|field| field.skip_none) {
+        let columns: Vec<&str> = fields.iter().map(|field| field.column.as_str()).collect();
+        let values = dollar_values(fields.len());
+        let upsert_values = format!(
+            "{} {}",
+            insert_values(&columns.join(", "), &values),
+            on_conflict_update(&columns, &keys, version)
+        );
+        return match version {
+            Some(_) => quote! { format!("{}{}", #upsert_values, #version_set) },
+            None => quote! { #upsert_values },
+        };
+    }
+
+    let version_column = match version {
+        Some(version) => quote! { Some(#version) },
+        None => quote! { None },
+    };
+
+    let pushes = column_pushes(fields);
+
+    quote! {
+        {
+            let keys: &[&str] = &[ #( #keys ),* ];
+            let mut columns: Vec<&str> = Vec::new();
+            #( #pushes )*
+            let values = (1..columns.len() + 1)
+                .map(|s| format!("${}", s))
+                .collect::<Vec<String>>()
+                .join(",");
+            let version: Option<&str> = #version_column;
+            let set = columns
+                .iter()
+                .filter(|column| !keys.contains(column) && Some(**column) != version)
+                .map(|column| format!("{} = excluded.{}", column, column))
+                .collect::<Vec<String>>();
+            let set = if set.is_empty() {
+                format!("{} = excluded.{}", keys[0], keys[0])
+            } else {
+                set.join(", ")
+            };
+            format!(
+                "( {} ) values ( {} ) on conflict ( {} ) do update set {}{}",
+                columns.join(", "),
+                values,
+                keys.join(", "),
+                set,
+                #version_set
+            )
+        }
+    }
+}
+
+/// "car_id" = $1 and "owner_id" = $2 with placeholders from `first`
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn key_where(keys: &[&str], first: usize) -> String {
+    keys.iter()
+        .enumerate()
+        .map(|(i, key)| format!("{} = ${}", key, first + i))
+        .collect::<Vec<String>>()
+        .join(" and ")
+}
+
+/// set car_name = $1 where car_id = $2 ( and owner_id = $3 for composite key )
+///
+/// Without columns to set first key is assigned to itself so the row is still matched ( and returned ).
+/// `version` column is incremented and matched by its current value bound last
+/// ( set car_name = $1, version = version + 1 where car_id = $2 and version = $3 ).
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn update_set(columns: &[&str], keys: &[&str], version: Option<&str>) -> String {
+    let mut set: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| format!("{} = ${}", column, i + 1))
+        .collect();
+    if let Some(version) = version {
+        set.push(format!("{} = {} + 1", version, version));
+    }
+    if set.is_empty() {
+        set.push(format!("{} = {}", keys[0], keys[0]));
+    }
+
+    let mut key_where = key_where(keys, columns.len() + 1);
+    if let Some(version) = version {
+        key_where = format!(
+            "{} and {} = ${}",
+            key_where,
+            version,
+            columns.len() + keys.len() + 1
+        );
+    }
+    format!("set {} where {}", set.join(", "), key_where)
+}
+
+/// set car_name = $1 where car_id = $2 as expression
+///
+/// `#[sqlxinsert(skip_none)]` fields which are `None` are not set and keep their value.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn update_set_expr(
+    fields: &[&Field],
+    keys: &[&Field],
+    version: Option<&Field>,
+) -> TokenStream2 {
+    let keys: Vec<&str> = keys.iter().map(|key| key.column.as_str()).collect();
+    let version = version.map(|version| version.column.as_str());
+    if !fields.iter().any(|field| field.skip_none) {
+        let columns: Vec<&str> = fields.iter().map(|field| field.column.as_str()).collect();
+        let update_set = update_set(&columns, &keys, version);
+        return quote! { #update_set };
+    }
+
+    let first_key = keys[0];
+    let pushes = column_pushes(fields);
+    let (version_set, version_where) = match version {
+        Some(version) => (
+            quote! { set.push(format!("{} = {} + 1", #version, #version)); },
+            quote! { let keys = format!("{} and {} = ${}", keys, #version, columns.len() + key_columns.len() + 1); },
+        ),
+        None => (quote! {}, quote! {}),
+    };
+
+    quote! {
+        {
+            let mut columns: Vec<&str> = Vec::new();
+            #( #pushes )*
+            let mut set = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| format!("{} = ${}", column, i + 1))
+                .collect::<Vec<String>>();
+            #version_set
+            if set.is_empty() {
+                set.push(format!("{} = {}", #first_key, #first_key));
+            }
+            let key_columns: &[&str] = &[ #( #keys ),* ];
+            let keys = key_columns
+                .iter()
+                .enumerate()
+                .map(|(i, key)| format!("{} = ${}", key, columns.len() + 1 + i))
+                .collect::<Vec<String>>()
+                .join(" and ");
+            #version_where
+            format!("set {} where {}", set.join(", "), keys)
+        }
+    }
+}
+
+/// columns.push("car_id"); ...
+///
+/// `#[sqlxinsert(skip_none)]` fields are pushed only when `Some`
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn column_pushes(fields: &[&Field]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident;
+            let column = &field.column;
+            if field.skip_none {
+                quote! {
+                    if self.#ident.is_some() {
+                        columns.push(#column);
+                    }
+                }
+            } else {
+                quote! { columns.push(#column); }
+            }
+        })
+        .collect()
+}
+
+/// let query = query.bind(&self.car_id); ...
+///
+/// `#[sqlxinsert(skip_none)]` fields are bound only when `Some` ( same as in `insert_values_expr` )
+pub(crate) fn bind_fields(fields: &[&Field]) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident;
+            if field.skip_none {
+                quote! {
+                    let query = if self.#ident.is_some() {
+                        query.bind(&self.#ident)
+                    } else {
+                        query
+                    };
+                }
+            } else {
+                quote! {
+                    let query = query.bind(&self.#ident);
+                }
+            }
+        })
+        .collect()
+}
+
+#[cfg(all(test, any(feature = "sqlite", feature = "postgres")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_values_test() {
+        assert_eq!(
+            insert_values("id, name", "$1,$2"),
+            "( id, name ) values ( $1,$2 )"
+        );
+        assert_eq!(insert_values("", ""), "default values");
+    }
+
+    #[test]
+    fn on_conflict_update_test() {
+        assert_eq!(
+            on_conflict_update(&["id", "name", "color"], &["id"], None),
+            "on conflict ( id ) do update set name = excluded.name, color = excluded.color"
+        );
+        assert_eq!(
+            on_conflict_update(&["car_id", "owner_id"], &["car_id", "owner_id"], None),
+            "on conflict ( car_id, owner_id ) do update set car_id = excluded.car_id"
+        );
+        assert_eq!(
+            on_conflict_update(&["id", "name", "version"], &["id"], Some("version")),
+            "on conflict ( id ) do update set name = excluded.name"
+        );
+    }
+
+    #[test]
+    fn key_where_test() {
+        assert_eq!(key_where(&["id"], 1), "id = $1");
+        assert_eq!(
+            key_where(&["car_id", "owner_id"], 2),
+            "car_id = $2 and owner_id = $3"
+        );
+    }
+
+    #[test]
+    fn update_set_test() {
+        assert_eq!(
+            update_set(&["name", "color"], &["id"], None),
+            "set name = $1, color = $2 where id = $3"
+        );
+        assert_eq!(update_set(&[], &["id"], None), "set id = id where id = $1");
+        assert_eq!(
+            update_set(&["since"], &["car_id", "owner_id"], None),
+            "set since = $1 where car_id = $2 and owner_id = $3"
+        );
+        assert_eq!(
+            update_set(&[], &["car_id", "owner_id"], None),
+            "set car_id = car_id where car_id = $1 and owner_id = $2"
+        );
+        assert_eq!(
+            update_set(&["name"], &["id"], Some("version")),
+            "set name = $1, version = version + 1 where id = $2 and version = $3"
+        );
+        assert_eq!(
+            update_set(&[], &["id"], Some("version")),
+            "set version = version + 1 where id = $1 and version = $2"
+        );
+    }
+}
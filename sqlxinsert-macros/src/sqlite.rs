@@ -0,0 +1,425 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attr::ContainerAttributes;
+use crate::field;
+use crate::{bound_generics, cached_sql, insertable_impl, query, table_const, table_method};
+
+/// Maximum bind parameters in one query ( `SQLITE_MAX_VARIABLE_NUMBER` )
+const MAX_BINDS: usize = 32766;
+
+pub(crate) fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let attributes = ContainerAttributes::from_ast(&input)?;
+
+    let all_fields = field::fields(&input, &attributes)?;
+    let fields = field::insert_fields(&all_fields);
+
+    // Attributes -> .bind(&self.field)
+    let binds = query::bind_fields(&fields);
+
+    let struct_name = &input.ident;
+    let generics = bound_generics(&input, &quote! { sqlx::Sqlite });
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // ( car_id, car_name ) values ( $1,$2 )
+    let insert_values = query::insert_values_expr(&fields);
+
+    let static_insert_values = query::static_insert_values(&fields);
+    let insert_sql = cached_sql(
+        static_insert_values.is_some(),
+        quote! { format!("insert into {} {}", table, #insert_values) },
+    );
+    let insert_returning_sql = cached_sql(
+        static_insert_values.is_some(),
+        quote! { format!("insert into {} {} returning *", table, #insert_values) },
+    );
+
+    let table_const = table_const(&attributes);
+    let insert_query = table_method(&attributes, "insert_query");
+    let insert_raw = table_method(&attributes, "insert_raw");
+    let insert = table_method(&attributes, "insert");
+    let insert_by_rowid = table_method(&attributes, "insert_by_rowid");
+
+    // Statements precomputed when both table and columns are known at compile time
+    let static_sql = match (&attributes.table, &static_insert_values) {
+        (Some(table), Some(insert_values)) => {
+            let sql = format!(
+                "insert into {} {}",
+                field::quote_table(table),
+                insert_values
+            );
+            let returning_sql = format!("{} returning *", sql);
+            Some(quote! {
+                /// Insert statement of `TABLE`
+                pub const INSERT_SQL: &'static str = #sql;
+                /// Insert statement of `TABLE` returning inserted row
+                pub const INSERT_RETURNING_SQL: &'static str = #returning_sql;
+            })
+        }
+        _ => None,
+    };
+
+    let table_insert_methods = match &static_sql {
+        Some(_) => quote! {
+            pub fn insert_query(&self) -> std::borrow::Cow<'static, str>
+            {
+                std::borrow::Cow::Borrowed(Self::INSERT_SQL)
+            }
+
+            pub async fn insert_raw<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+            {
+                let query = sqlx::query(Self::INSERT_SQL);
+                #( #binds )*
+                Ok(query.execute(executor).await?)
+            }
+
+            pub async fn insert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::sqlite::SqliteRow>,
+                Row: std::marker::Unpin
+            {
+                let query = sqlx::query_as::<_,Row>(Self::INSERT_RETURNING_SQL);
+                #( #binds )*
+                Ok(query.fetch_one(executor).await?)
+            }
+        },
+        None => quote! {
+            pub fn insert_query(&self) -> std::borrow::Cow<'static, str>
+            {
+                self.#insert_query(Self::TABLE)
+            }
+
+            pub async fn insert_raw<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+            {
+                self.#insert_raw(executor, Self::TABLE).await
+            }
+
+            pub async fn insert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::sqlite::SqliteRow>,
+                Row: std::marker::Unpin
+            {
+                self.#insert(executor, Self::TABLE).await
+            }
+        },
+    };
+
+    // Table-less variants when table is known at compile time
+    let table_methods = match &attributes.table {
+        Some(_) => quote! {
+            #table_insert_methods
+
+            pub async fn insert_by_rowid<'acquire, Row>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::sqlite::SqliteRow>,
+                Row: std::marker::Unpin
+            {
+                self.#insert_by_rowid(conn, Self::TABLE).await
+            }
+        },
+        None => quote! {},
+    };
+
+    // Optimistic locking by `#[sqlxinsert(version)]` in update and upsert
+    let version = field::version(&all_fields)?;
+    // No matched row with version column means the row was changed since it was read
+    let stale_check = version.map(|_| {
+        quote! {
+            if res.rows_affected() == 0 {
+                return Err(::sqlxinsert::InsertError::StaleObject);
+            }
+        }
+    });
+
+    // Update by `#[sqlxinsert(primary_key)]`
+    let primary_keys = field::primary_keys(&all_fields);
+    let update_methods = match primary_keys.as_slice() {
+        [] => quote! {},
+        keys => {
+            let update_fields = field::update_fields(&all_fields);
+            let update_binds = query::bind_fields(&update_fields);
+            // set car_name = $1 where car_id = $2 ( and owner_id = $3 )
+            let update_set = query::update_set_expr(&update_fields, keys, version);
+            let key_names = keys.iter().map(|key| key.ident);
+            let version_bind = version.map(|version| {
+                let version_name = version.ident;
+                quote! { let query = query.bind(&self.#version_name); }
+            });
+
+            let update_query = table_method(&attributes, "update_query");
+            let update = table_method(&attributes, "update");
+
+            let update_table_methods = match &attributes.table {
+                Some(_) => quote! {
+                    pub fn update_query(&self) -> String
+                    {
+                        self.#update_query(Self::TABLE)
+                    }
+
+                    pub async fn update<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+                    {
+                        self.#update(executor, Self::TABLE).await
+                    }
+                },
+                None => quote! {},
+            };
+
+            quote! {
+                pub fn #update_query(&self, table: impl Into<::sqlxinsert::TableName>) -> String
+                {
+                    let table: ::sqlxinsert::TableName = table.into();
+                    format!("update {} {}", table, #update_set)
+                }
+
+                pub async fn #update<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+                {
+                    let table: ::sqlxinsert::TableName = table.into();
+                    let sql = self.#update_query(&table);
+                    let query = sqlx::query(&sql);
+                    #( #update_binds )*
+                    #( let query = query.bind(&self.#key_names); )*
+                    #version_bind
+                    let res = query.execute(executor).await?;
+                    #stale_check
+                    Ok(res)
+                }
+
+                #update_table_methods
+            }
+        }
+    };
+
+    // Upsert on `#[sqlxinsert(conflict_key)]` ( or primary key )
+    let conflict_keys = field::conflict_keys(&all_fields);
+    let upsert_methods = if conflict_keys.is_empty() {
+        quote! {}
+    } else {
+        // ( car_id, car_name ) values ( $1,$2 ) on conflict ( car_id ) do update set car_name = excluded.car_name
+        let upsert_values = query::upsert_values_expr(&fields, &conflict_keys, version);
+        let conflict_columns = field::columns(&conflict_keys);
+
+        let upsert_query = table_method(&attributes, "upsert_query");
+        let upsert = table_method(&attributes, "upsert");
+        let insert_or_ignore_query = table_method(&attributes, "insert_or_ignore_query");
+        let insert_or_ignore = table_method(&attributes, "insert_or_ignore");
+
+        let upsert_table_methods = match &attributes.table {
+            Some(_) => quote! {
+                pub fn upsert_query(&self) -> String
+                {
+                    self.#upsert_query(Self::TABLE)
+                }
+
+                pub fn insert_or_ignore_query(&self) -> String
+                {
+                    self.#insert_or_ignore_query(Self::TABLE)
+                }
+
+                pub async fn upsert<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+                {
+                    self.#upsert(executor, Self::TABLE).await
+                }
+
+                pub async fn insert_or_ignore<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+                {
+                    self.#insert_or_ignore(executor, Self::TABLE).await
+                }
+            },
+            None => quote! {},
+        };
+
+        quote! {
+            pub fn #upsert_query(&self, table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} {}", table, #upsert_values)
+            }
+
+            pub fn #insert_or_ignore_query(&self, table: impl Into<::sqlxinsert::TableName>) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} {} on conflict ( {} ) do nothing", table, #insert_values, #conflict_columns)
+            }
+
+            pub async fn #upsert<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#upsert_query(&table);
+                let query = sqlx::query(&sql);
+                #( #binds )*
+                let res = query.execute(executor).await?;
+                #stale_check
+                Ok(res)
+            }
+
+            /// Rows affected is 0 when row already exists
+            pub async fn #insert_or_ignore<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#insert_or_ignore_query(&table);
+                let query = sqlx::query(&sql);
+                #( #binds )*
+                Ok(query.execute(executor).await?)
+            }
+
+            #upsert_table_methods
+        }
+    };
+
+    // Multi-row insert, not possible without columns
+    let insert_many_methods = if fields.is_empty() {
+        quote! {}
+    } else {
+        let columns = field::columns(&fields);
+        // ( $1,$2 ),( $3,$4 )
+        let insert_many_values = query::insert_many_values_expr(&fields);
+        let rows_per_insert = query::rows_per_insert(MAX_BINDS, &fields);
+        let field_name = fields.iter().map(|field| field.ident);
+
+        let insert_many_query = table_method(&attributes, "insert_many_query");
+        let insert_many = table_method(&attributes, "insert_many");
+
+        let insert_many_table_methods = match &attributes.table {
+            Some(_) => quote! {
+                pub fn insert_many_query(rows: usize) -> String
+                {
+                    Self::#insert_many_query(Self::TABLE, rows)
+                }
+
+                pub async fn insert_many<'acquire>(rows: &[Self], conn: impl sqlx::Acquire<'acquire, Database = sqlx::Sqlite>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+                {
+                    Self::#insert_many(rows, conn, Self::TABLE).await
+                }
+            },
+            None => quote! {},
+        };
+
+        quote! {
+            pub fn #insert_many_query(table: impl Into<::sqlxinsert::TableName>, rows: usize) -> String
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                format!("insert into {} ( {} ) values {}", table, #columns, #insert_many_values)
+            }
+
+            /// Rows are inserted in chunks so bind parameters stay under SQLite limit
+            ///
+            /// `skip_none` is not applied, `None` is inserted as `NULL`
+            pub async fn #insert_many<'acquire>(rows: &[Self], conn: impl sqlx::Acquire<'acquire, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let mut tx = conn.begin().await?;
+                let mut res = sqlx::sqlite::SqliteQueryResult::default();
+                for chunk in rows.chunks(#rows_per_insert) {
+                    let sql = Self::#insert_many_query(&table, chunk.len());
+                    let mut query = sqlx::query(&sql);
+                    for row in chunk {
+                        #( query = query.bind(&row.#field_name); )*
+                    }
+                    res.extend(Some(query.execute(&mut tx).await?));
+                }
+                tx.commit().await?;
+                Ok(res)
+            }
+
+            #insert_many_table_methods
+        }
+    };
+
+    let insertable = insertable_impl(
+        &input,
+        &attributes,
+        quote! { sqlx::Sqlite },
+        fields.iter().map(|field| field.column.clone()).collect(),
+        &binds,
+        quote! {
+            let sql = #insert_returning_sql;
+            let mut conn = conn.acquire().await?;
+            let query = sqlx::query_as::<_,Row>(&sql);
+            #( #binds )*
+            Ok(query.fetch_one(&mut *conn).await?)
+        },
+    );
+    if attributes.trait_only {
+        return Ok(insertable);
+    }
+
+    Ok(quote! {
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            #table_const
+
+            #static_sql
+
+            pub fn #insert_query(&self, table: impl Into<::sqlxinsert::TableName>) -> std::borrow::Cow<'static, str>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                #insert_sql
+            }
+
+            pub async fn #insert_raw<'executor>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<sqlx::sqlite::SqliteQueryResult>
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = self.#insert_query(&table);
+                let query = sqlx::query(&sql);
+                #( #binds )*
+                Ok(query
+                    .execute(executor)
+                    .await?
+                )
+            }
+
+            /// Inserted row is returned with `returning *` ( SQLite 3.35+ )
+            pub async fn #insert<'executor, Row>(&self, executor: impl sqlx::Executor<'executor, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::sqlite::SqliteRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let sql = #insert_returning_sql;
+
+                let query = sqlx::query_as::<_,Row>(&sql);
+                #( #binds )*
+                let res: Row = query
+                    .fetch_one(executor)
+                    .await?;
+
+                Ok(res)
+            }
+
+            /// Inserted row is selected again by `last_insert_rowid()` for SQLite without `returning`
+            pub async fn #insert_by_rowid<'acquire, Row>(&self, conn: impl sqlx::Acquire<'acquire, Database = sqlx::Sqlite>, table: impl Into<::sqlxinsert::TableName>) -> ::sqlxinsert::Result<Row>
+            where
+                Row: Send,
+                Row: for<'row> sqlx::FromRow<'row, sqlx::sqlite::SqliteRow>,
+                Row: std::marker::Unpin
+            {
+                let table: ::sqlxinsert::TableName = table.into();
+                let mut conn = conn.acquire().await?;
+                let res = self.#insert_raw(&mut *conn, &table).await?;
+
+                let sql = format!("select * from {} where rowid = $1", table);
+                let res: Row = sqlx::query_as::<_,Row>(&sql)
+                    .bind(res.last_insert_rowid())
+                    .fetch_one(&mut *conn)
+                    .await?;
+
+                Ok(res)
+            }
+
+            #table_methods
+
+            #update_methods
+
+            #upsert_methods
+
+            #insert_many_methods
+        }
+
+        #insertable
+    })
+}
@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+use std::sync::RwLock;
+
+use crate::TableName;
+
+/// Tables cached by one statement cache, statements of other tables are built on every call
+const MAX_TABLES: usize = 64;
+
+/// Statement cache of generated methods keyed by table
+///
+/// Statements are built once per table and leaked so queries get identical `&'static str`
+/// ( number of tables is limited by `MAX_TABLES` ).
+#[doc(hidden)]
+pub struct SqlCache {
+    statements: RwLock<Vec<(TableName, &'static str)>>,
+}
+
+impl SqlCache {
+    pub const fn new() -> Self {
+        SqlCache {
+            statements: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// Cached statement of `table` or statement built by `sql`
+    pub fn get(
+        &self,
+        table: &TableName,
+        sql: impl FnOnce(&TableName) -> String,
+    ) -> Cow<'static, str> {
+        if let Some(statement) = Self::find(&self.statements.read().unwrap(), table) {
+            return Cow::Borrowed(statement);
+        }
+
+        let mut statements = self.statements.write().unwrap();
+        if let Some(statement) = Self::find(&statements, table) {
+            return Cow::Borrowed(statement);
+        }
+        if statements.len() >= MAX_TABLES {
+            return Cow::Owned(sql(table));
+        }
+        let statement: &'static str = Box::leak(sql(table).into_boxed_str());
+        statements.push((table.clone(), statement));
+        Cow::Borrowed(statement)
+    }
+
+    fn find(statements: &[(TableName, &'static str)], table: &TableName) -> Option<&'static str> {
+        statements
+            .iter()
+            .find(|(cached, _)| cached == table)
+            .map(|(_, statement)| *statement)
+    }
+}
+
+impl Default for SqlCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,187 @@
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Result of generated methods
+pub type Result<T, E = InsertError> = std::result::Result<T, E>;
+
+/// Error of generated methods with constraint violations decoded from database error codes
+///
+/// Postgres SQLSTATE, SQLite extended result codes and MySQL error numbers are recognized, any
+/// other error is kept in `Database`.
+///
+/// ```rust,ignore
+/// match car.insert::<Car>(&pool).await {
+///     Ok(car) => println!("{:?}", car),
+///     Err(InsertError::UniqueViolation { constraint, .. }) => println!("exists {:?}", constraint),
+///     Err(err) => return Err(err.into()),
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum InsertError {
+    /// Unique or primary key constraint ( `23505`, `SQLITE_CONSTRAINT_UNIQUE`, MySQL `1062` )
+    UniqueViolation {
+        /// Constraint name ( Postgres ) or constrained columns ( SQLite )
+        constraint: Option<String>,
+        source: sqlx::Error,
+    },
+    /// Foreign key constraint ( `23503`, `SQLITE_CONSTRAINT_FOREIGNKEY`, MySQL `1451` / `1452` )
+    ForeignKeyViolation {
+        constraint: Option<String>,
+        source: sqlx::Error,
+    },
+    /// `NOT NULL` column ( `23502`, `SQLITE_CONSTRAINT_NOTNULL`, MySQL `1048` )
+    NotNullViolation {
+        /// Column name ( SQLite `table.column` )
+        column: Option<String>,
+        source: sqlx::Error,
+    },
+    /// Check constraint ( `23514`, `SQLITE_CONSTRAINT_CHECK`, MySQL `3819` )
+    CheckViolation {
+        constraint: Option<String>,
+        source: sqlx::Error,
+    },
+    /// Any other database or driver error
+    Database(sqlx::Error),
+    /// `Insertable::insert` on struct without `#[sqlxinsert(table = "...")]`
+    MissingTable,
+    /// Update matched no row with `#[sqlxinsert(version)]` value, row was changed or deleted
+    /// since it was read ( reload and retry )
+    StaleObject,
+}
+
+impl InsertError {
+    /// Underlying sqlx error
+    pub fn sqlx_error(&self) -> Option<&sqlx::Error> {
+        match self {
+            InsertError::UniqueViolation { source, .. }
+            | InsertError::ForeignKeyViolation { source, .. }
+            | InsertError::NotNullViolation { source, .. }
+            | InsertError::CheckViolation { source, .. }
+            | InsertError::Database(source) => Some(source),
+            InsertError::MissingTable | InsertError::StaleObject => None,
+        }
+    }
+}
+
+/// Kind of constraint violation
+enum Violation {
+    Unique,
+    ForeignKey,
+    NotNull,
+    Check,
+}
+
+/// Postgres SQLSTATE and SQLite extended result code ( decimal ) -> violation
+fn violation(code: &str) -> Option<Violation> {
+    match code {
+        "23505" | "2067" | "1555" => Some(Violation::Unique),
+        "23503" | "787" => Some(Violation::ForeignKey),
+        "23502" | "1299" => Some(Violation::NotNull),
+        "23514" | "275" => Some(Violation::Check),
+        _ => None,
+    }
+}
+
+/// MySQL error number -> violation ( SQLSTATE is `23000` for all of them )
+#[cfg(feature = "mysql")]
+fn mysql_violation(err: &(dyn sqlx::error::DatabaseError + 'static)) -> Option<Violation> {
+    let err = err.try_downcast_ref::<sqlx::mysql::MySqlDatabaseError>()?;
+    match err.number() {
+        1062 => Some(Violation::Unique),
+        1451 | 1452 => Some(Violation::ForeignKey),
+        1048 => Some(Violation::NotNull),
+        3819 => Some(Violation::Check),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "mysql"))]
+fn mysql_violation(_err: &(dyn sqlx::error::DatabaseError + 'static)) -> Option<Violation> {
+    None
+}
+
+impl From<sqlx::Error> for InsertError {
+    fn from(source: sqlx::Error) -> Self {
+        let (violation, constraint) = match &source {
+            sqlx::Error::Database(err) => {
+                let violation = err
+                    .code()
+                    .and_then(|code| violation(&code))
+                    .or_else(|| mysql_violation(err.as_ref()));
+                // SQLite has no constraint name: "UNIQUE constraint failed: cars.name"
+                let constraint = err
+                    .constraint()
+                    .or_else(|| {
+                        err.message()
+                            .split_once("failed: ")
+                            .map(|(_, columns)| columns)
+                    })
+                    .map(|constraint| constraint.to_string());
+                (violation, constraint)
+            }
+            _ => (None, None),
+        };
+
+        match violation {
+            Some(Violation::Unique) => InsertError::UniqueViolation { constraint, source },
+            Some(Violation::ForeignKey) => InsertError::ForeignKeyViolation { constraint, source },
+            Some(Violation::NotNull) => InsertError::NotNullViolation {
+                column: column(&source).or(constraint),
+                source,
+            },
+            Some(Violation::Check) => InsertError::CheckViolation { constraint, source },
+            None => InsertError::Database(source),
+        }
+    }
+}
+
+/// Column of Postgres `NOT NULL` violation
+#[cfg(feature = "postgres")]
+fn column(source: &sqlx::Error) -> Option<String> {
+    match source {
+        sqlx::Error::Database(err) => err
+            .try_downcast_ref::<sqlx::postgres::PgDatabaseError>()
+            .and_then(|err| err.column())
+            .map(|column| column.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(not(feature = "postgres"))]
+fn column(_source: &sqlx::Error) -> Option<String> {
+    None
+}
+
+impl fmt::Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::UniqueViolation { source, .. } => {
+                write!(f, "unique constraint violation: {}", source)
+            }
+            InsertError::ForeignKeyViolation { source, .. } => {
+                write!(f, "foreign key constraint violation: {}", source)
+            }
+            InsertError::NotNullViolation { source, .. } => {
+                write!(f, "not null constraint violation: {}", source)
+            }
+            InsertError::CheckViolation { source, .. } => {
+                write!(f, "check constraint violation: {}", source)
+            }
+            InsertError::Database(source) => write!(f, "{}", source),
+            InsertError::MissingTable => {
+                write!(f, "table is not set by `#[sqlxinsert(table = \"...\")]`")
+            }
+            InsertError::StaleObject => {
+                write!(f, "row was changed or deleted since it was read")
+            }
+        }
+    }
+}
+
+impl StdError for InsertError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.sqlx_error()
+            .map(|err| err as &(dyn StdError + 'static))
+    }
+}
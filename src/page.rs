@@ -0,0 +1,9 @@
+/// Rows of one page with cursor of the following page
+///
+/// `next` is `None` on the last page. Keyset pages ( `page` ) use value of order key as cursor,
+/// offset pages ( `page_offset` ) use offset of the following page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T, C> {
+    pub items: Vec<T>,
+    pub next: Option<C>,
+}
@@ -0,0 +1,138 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Table name quoted as identifier in generated queries
+///
+/// Schema qualified names are split on first dot and both parts are quoted so table name coming
+/// from user input cannot change the query.
+///
+/// ```rust
+/// use sqlxinsert::TableName;
+///
+/// let table = TableName::from("analytics.events");
+/// assert_eq!(table.to_string(), r#""analytics"."events""#);
+/// assert_eq!(table.backtick_quoted(), "`analytics`.`events`");
+///
+/// // Quotes are escaped
+/// assert_eq!(TableName::new(r#"order"s"#).to_string(), r#""order""s""#);
+///
+/// // Validated parsing
+/// assert!("tenant_1.cars".parse::<TableName>().is_ok());
+/// assert!("a.b.c".parse::<TableName>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TableName {
+    schema: Option<String>,
+    name: String,
+}
+
+impl TableName {
+    /// Table without schema, name is used as is ( dots are not split )
+    pub fn new(name: impl Into<String>) -> Self {
+        TableName {
+            schema: None,
+            name: name.into(),
+        }
+    }
+
+    /// Table in `schema`
+    pub fn with_schema(schema: impl Into<String>, name: impl Into<String>) -> Self {
+        TableName {
+            schema: Some(schema.into()),
+            name: name.into(),
+        }
+    }
+
+    pub fn schema(&self) -> Option<&str> {
+        self.schema.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `schema`.`table` for MySQL
+    pub fn backtick_quoted(&self) -> String {
+        self.quoted('`')
+    }
+
+    /// "schema"."table" ( doubled quote inside identifier )
+    fn quoted(&self, quote: char) -> String {
+        let escape = |ident: &str| {
+            format!(
+                "{}{}{}",
+                quote,
+                ident.replace(quote, &format!("{}{}", quote, quote)),
+                quote
+            )
+        };
+        match &self.schema {
+            Some(schema) => format!("{}.{}", escape(schema), escape(&self.name)),
+            None => escape(&self.name),
+        }
+    }
+}
+
+/// "schema"."table" for Postgres and SQLite
+impl fmt::Display for TableName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.quoted('"'))
+    }
+}
+
+/// cars -> "cars", analytics.events -> "analytics"."events"
+impl From<&str> for TableName {
+    fn from(table: &str) -> Self {
+        match table.split_once('.') {
+            Some((schema, name)) => TableName::with_schema(schema, name),
+            None => TableName::new(table),
+        }
+    }
+}
+
+impl From<&String> for TableName {
+    fn from(table: &String) -> Self {
+        TableName::from(table.as_str())
+    }
+}
+
+impl From<String> for TableName {
+    fn from(table: String) -> Self {
+        TableName::from(table.as_str())
+    }
+}
+
+impl From<&TableName> for TableName {
+    fn from(table: &TableName) -> Self {
+        table.clone()
+    }
+}
+
+/// Table name rejected by `TableName::from_str`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidTableName(String);
+
+impl fmt::Display for InvalidTableName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid table name `{}`", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTableName {}
+
+/// `table` or `schema.table` with non-empty parts without NUL characters
+impl FromStr for TableName {
+    type Err = InvalidTableName;
+
+    fn from_str(table: &str) -> Result<Self, Self::Err> {
+        let valid = |ident: &str| !ident.is_empty() && !ident.contains('\0');
+        let parts: Vec<&str> = table.split('.').collect();
+        match parts.as_slice() {
+            [name] if valid(name) => Ok(TableName::new(*name)),
+            [schema, name] if valid(schema) && valid(name) => {
+                Ok(TableName::with_schema(*schema, *name))
+            }
+            _ => Err(InvalidTableName(table.to_string())),
+        }
+    }
+}
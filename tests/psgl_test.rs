@@ -1,3 +1,5 @@
+#![cfg(feature = "postgres")]
+
 // extern crate we're testing, same as any other code will do.
 //extern crate gmacro;
 
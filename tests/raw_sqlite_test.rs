@@ -1,9 +1,12 @@
+#![cfg(feature = "sqlite")]
+
 #[derive(Default, Debug, sqlx::FromRow)]
 struct Car {
     pub car_id: i32,
     pub car_name: String,
 }
 #[derive(Default, Debug, sqlx::FromRow)]
+#[allow(dead_code)]
 struct CreateCar {
     #[allow(dead_code)]
     pub car_name: String,
@@ -0,0 +1,66 @@
+#![cfg(all(feature = "any", feature = "sqlite"))]
+
+#[derive(Default, Debug, std::cmp::PartialEq, sqlx::FromRow)]
+struct Car {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::Insert)]
+#[sqlxinsert(table = "cars")]
+struct CreateCar {
+    pub name: String,
+    #[sqlxinsert(skip_none)]
+    pub color: Option<String>,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_any_insert() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::any::AnyPoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        color TEXT NOT NULL DEFAULT 'white'
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let car_skoda = CreateCar {
+        name: "Skoda".to_string(),
+        color: None,
+    };
+    assert_eq!(
+        car_skoda.insert_query(pool.any_kind()),
+        r#"insert into "cars" ( "name" ) values ( $1 )"#
+    );
+
+    let res = car_skoda.insert_raw(&pool).await.unwrap();
+    assert_eq!(res.last_insert_id(), Some(1));
+
+    let car_tesla = CreateCar {
+        name: "Tesla".to_string(),
+        color: Some("red".to_string()),
+    };
+    let mut tx = pool.begin().await.unwrap();
+    let car: Car = car_tesla.insert::<Car>(&mut tx).await.unwrap();
+    tx.commit().await.unwrap();
+
+    assert_eq!(
+        car,
+        Car {
+            id: 2,
+            name: "Tesla".to_string(),
+            color: "red".to_string(),
+        }
+    );
+}
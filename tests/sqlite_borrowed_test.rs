@@ -0,0 +1,122 @@
+#![cfg(feature = "sqlite")]
+
+use sqlxinsert::Insertable;
+
+#[derive(Default, Debug, std::cmp::PartialEq, sqlx::FromRow)]
+struct Post {
+    pub id: i64,
+    pub title: String,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "posts")]
+struct NewPost<'a> {
+    pub title: &'a str,
+    #[sqlxinsert(skip_none)]
+    pub body: Option<&'a str>,
+}
+
+#[derive(Default, Debug, std::cmp::PartialEq, sqlx::FromRow)]
+struct Setting {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "settings")]
+struct NewSetting<T> {
+    #[sqlxinsert(primary_key)]
+    pub name: String,
+    pub value: T,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_borrowed() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table posts (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        title TEXT NOT NULL,
+        body TEXT
+    )";
+    sqlx::query(create_table).execute(&pool).await.unwrap();
+
+    let create_table = "create table settings (
+        name TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    )";
+    sqlx::query(create_table).execute(&pool).await.unwrap();
+
+    // Lifetime parameter, values are borrowed
+    let title = String::from("Hello");
+    let post = NewPost {
+        title: &title,
+        body: None,
+    };
+    let res: Post = post.insert::<Post>(&pool).await.unwrap();
+    assert_eq!(
+        res,
+        Post {
+            id: 1,
+            title: "Hello".to_string(),
+            body: None,
+        }
+    );
+
+    let lines = ["First", "Second"];
+    let posts: Vec<NewPost> = lines
+        .iter()
+        .map(|line| NewPost {
+            title: line,
+            body: Some(line),
+        })
+        .collect();
+    NewPost::insert_many(&posts, &pool).await.unwrap();
+
+    // Type parameter
+    let setting = NewSetting {
+        name: "seats".to_string(),
+        value: 5,
+    };
+    setting.insert_raw(&pool).await.unwrap();
+
+    let setting = NewSetting {
+        name: "color".to_string(),
+        value: "red".to_string(),
+    };
+    let res: Setting = Insertable::<sqlx::Sqlite>::insert(&setting, &pool)
+        .await
+        .unwrap();
+    assert_eq!(res.value, "red");
+
+    let setting = NewSetting {
+        name: "color".to_string(),
+        value: "blue".to_string(),
+    };
+    setting.update(&pool).await.unwrap();
+
+    let settings: Vec<Setting> = sqlx::query_as("select * from settings order by name")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(
+        settings,
+        vec![
+            Setting {
+                name: "color".to_string(),
+                value: "blue".to_string(),
+            },
+            Setting {
+                name: "seats".to_string(),
+                value: "5".to_string(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(
+    Default,
+    Debug,
+    std::cmp::PartialEq,
+    sqlx::FromRow,
+    sqlxinsert::SqliteInsert,
+    sqlxinsert::SqliteCrud,
+)]
+#[sqlxinsert(table = "car_owner")]
+struct CarOwner {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    #[sqlxinsert(primary_key)]
+    pub owner_id: i32,
+    pub since: i32,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_composite_key() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table car_owner (
+        car_id INTEGER NOT NULL,
+        owner_id INTEGER NOT NULL,
+        since INTEGER NOT NULL,
+        PRIMARY KEY ( car_id, owner_id )
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    for (car_id, owner_id) in [(1, 1), (1, 2), (2, 1)] {
+        let row = CarOwner {
+            car_id,
+            owner_id,
+            since: 2000,
+        };
+        row.insert_raw(&pool).await.unwrap();
+    }
+
+    let row = CarOwner {
+        car_id: 1,
+        owner_id: 2,
+        since: 2010,
+    };
+    assert_eq!(
+        row.update_query(),
+        r#"update "car_owner" set "since" = $1 where "car_id" = $2 and "owner_id" = $3"#
+    );
+    assert_eq!(row.update(&pool).await.unwrap().rows_affected(), 1);
+
+    let row = CarOwner {
+        car_id: 2,
+        owner_id: 1,
+        since: 2020,
+    };
+    assert_eq!(
+        row.upsert_query(),
+        r#"insert into "car_owner" ( "car_id", "owner_id", "since" ) values ( $1,$2,$3 ) on conflict ( "car_id", "owner_id" ) do update set "since" = excluded."since""#
+    );
+    row.upsert(&pool).await.unwrap();
+
+    let row = CarOwner::find_by_id::<CarOwner>(&pool, (1, 2))
+        .await
+        .unwrap();
+    assert_eq!(row.map(|row| row.since), Some(2010));
+    let row = CarOwner::find_by_id::<CarOwner>(&pool, (2, 1))
+        .await
+        .unwrap();
+    assert_eq!(row.map(|row| row.since), Some(2020));
+    assert_eq!(
+        CarOwner::find_by_id::<CarOwner>(&pool, (2, 2))
+            .await
+            .unwrap(),
+        None
+    );
+
+    let rows = CarOwner::find_all::<CarOwner>(&pool).await.unwrap();
+    let ids: Vec<(i32, i32)> = rows.iter().map(|row| (row.car_id, row.owner_id)).collect();
+    assert_eq!(ids, vec![(1, 1), (1, 2), (2, 1)]);
+
+    assert!(CarOwner::exists(&pool, (1, 1)).await.unwrap());
+    assert_eq!(CarOwner::delete_by_id(&pool, (1, 1)).await.unwrap(), 1);
+    assert!(!CarOwner::exists(&pool, (1, 1)).await.unwrap());
+    assert_eq!(CarOwner::count(&pool).await.unwrap(), 2);
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(
+    Default,
+    Debug,
+    std::cmp::PartialEq,
+    sqlx::FromRow,
+    sqlxinsert::SqliteInsert,
+    sqlxinsert::SqliteCrud,
+)]
+#[sqlxinsert(table = "cars")]
+struct Car {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteCrud)]
+struct CarKey {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_crud() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    for table in ["cars", "old_cars"] {
+        let create_table = format!(
+            "create table {} (
+                car_id INTEGER PRIMARY KEY,
+                car_name TEXT NOT NULL
+            )",
+            table
+        );
+        sqlx::query(&create_table)
+            .execute(&pool)
+            .await
+            .expect("Not possible to execute");
+    }
+
+    for (car_id, car_name) in [(2, "Tesla"), (1, "Skoda")] {
+        let car = Car {
+            car_id,
+            car_name: car_name.to_string(),
+        };
+        car.insert_raw(&pool).await.unwrap();
+    }
+
+    let car = Car::find_by_id::<Car>(&pool, 1).await.unwrap();
+    assert_eq!(car.map(|car| car.car_name).as_deref(), Some("Skoda"));
+    assert_eq!(Car::find_by_id::<Car>(&pool, 3).await.unwrap(), None);
+
+    let cars = Car::find_all::<Car>(&pool).await.unwrap();
+    let ids: Vec<i32> = cars.iter().map(|car| car.car_id).collect();
+    assert_eq!(ids, vec![1, 2]);
+
+    assert!(Car::exists(&pool, 2).await.unwrap());
+    assert!(!Car::exists(&pool, 3).await.unwrap());
+    assert_eq!(Car::count(&pool).await.unwrap(), 2);
+
+    assert_eq!(Car::delete_by_id(&pool, 2).await.unwrap(), 1);
+    assert_eq!(Car::delete_by_id(&pool, 2).await.unwrap(), 0);
+    assert_eq!(Car::count(&pool).await.unwrap(), 1);
+
+    // Explicit table
+    assert_eq!(Car::count_in(&pool, "old_cars").await.unwrap(), 0);
+    let car = Car {
+        car_id: 5,
+        car_name: "Trabant".to_string(),
+    };
+    car.insert_raw_in(&pool, "old_cars").await.unwrap();
+    assert!(CarKey::exists(&pool, 5, "old_cars").await.unwrap());
+    let keys = CarKey::find_all::<CarKey>(&pool, "old_cars").await.unwrap();
+    assert_eq!(keys[0].car_id, 5);
+    let car = CarKey::find_by_id::<Car>(&pool, 5, "old_cars")
+        .await
+        .unwrap();
+    assert_eq!(car.unwrap().car_name, "Trabant");
+    assert_eq!(CarKey::delete_by_id(&pool, 5, "old_cars").await.unwrap(), 1);
+    assert!(CarKey::find_all::<Car>(&pool, "old_cars")
+        .await
+        .unwrap()
+        .is_empty());
+}
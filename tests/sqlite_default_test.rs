@@ -0,0 +1,53 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Car {
+    #[sqlxinsert(default)]
+    pub car_id: i32,
+    pub car_name: String,
+    #[sqlxinsert(skip)]
+    #[sqlx(default)]
+    pub selected: bool,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_default() {
+    let car = Car {
+        car_name: "Skoda".to_string(),
+        ..Default::default()
+    };
+
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    assert_eq!(
+        car.insert_query("cars"),
+        r#"insert into "cars" ( "car_name" ) values ( $1 )"#
+    );
+
+    car.insert_raw(&pool, "cars").await.unwrap();
+    let res = car.insert_raw(&pool, "cars").await.unwrap();
+    assert_eq!(res.last_insert_rowid(), 2);
+
+    let rows = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].car_id, 2);
+    assert!(!rows[1].selected);
+}
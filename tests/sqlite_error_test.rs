@@ -0,0 +1,93 @@
+#![cfg(feature = "sqlite")]
+
+use sqlxinsert::InsertError;
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "owners")]
+struct CreateOwner {
+    pub owner_id: i32,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "cars")]
+struct CreateCar {
+    pub car_id: i32,
+    pub owner_id: i32,
+    pub car_name: Option<String>,
+    pub seats: i32,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_error() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    sqlx::query("PRAGMA foreign_keys = ON")
+        .execute(&pool)
+        .await
+        .unwrap();
+    sqlx::query("create table owners ( owner_id INTEGER PRIMARY KEY )")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        owner_id INTEGER NOT NULL REFERENCES owners ( owner_id ),
+        car_name TEXT NOT NULL UNIQUE,
+        seats INTEGER NOT NULL CHECK ( seats > 0 )
+    )";
+    sqlx::query(create_table).execute(&pool).await.unwrap();
+
+    CreateOwner { owner_id: 1 }.insert_raw(&pool).await.unwrap();
+
+    let car = |car_id: i32, owner_id: i32, car_name: Option<&str>, seats: i32| CreateCar {
+        car_id,
+        owner_id,
+        car_name: car_name.map(|name| name.to_string()),
+        seats,
+    };
+
+    car(1, 1, Some("Skoda"), 5).insert_raw(&pool).await.unwrap();
+
+    match car(2, 1, Some("Skoda"), 5).insert_raw(&pool).await {
+        Err(InsertError::UniqueViolation { constraint, .. }) => {
+            assert_eq!(constraint.as_deref(), Some("cars.car_name"))
+        }
+        other => panic!("expected unique violation, got {:?}", other),
+    }
+
+    match car(1, 1, Some("Tesla"), 5).insert_raw(&pool).await {
+        Err(InsertError::UniqueViolation { .. }) => {}
+        other => panic!("expected primary key violation, got {:?}", other),
+    }
+
+    match car(3, 2, Some("Tesla"), 5).insert_raw(&pool).await {
+        Err(InsertError::ForeignKeyViolation { .. }) => {}
+        other => panic!("expected foreign key violation, got {:?}", other),
+    }
+
+    match car(4, 1, None, 5).insert_raw(&pool).await {
+        Err(InsertError::NotNullViolation { column, .. }) => {
+            assert_eq!(column.as_deref(), Some("cars.car_name"))
+        }
+        other => panic!("expected not null violation, got {:?}", other),
+    }
+
+    match car(5, 1, Some("Tesla"), 0).insert_raw(&pool).await {
+        Err(InsertError::CheckViolation { .. }) => {}
+        other => panic!("expected check violation, got {:?}", other),
+    }
+
+    match car(6, 1, Some("Tesla"), 5)
+        .insert_raw_in(&pool, "bikes")
+        .await
+    {
+        Err(InsertError::Database(sqlx::Error::Database(_))) => {}
+        other => panic!("expected database error, got {:?}", other),
+    }
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Car {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+async fn insert_in_transaction(tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>, car: &Car) {
+    car.insert_raw(&mut *tx, "cars").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_executor() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let car = |car_id: i32| Car {
+        car_id,
+        car_name: "Skoda".to_string(),
+    };
+
+    // Pool
+    car(1).insert_raw(&pool, "cars").await.unwrap();
+
+    // Connection
+    let mut conn = pool.acquire().await.unwrap();
+    car(2).insert_raw(&mut *conn, "cars").await.unwrap();
+    car(2).update(&mut *conn, "cars").await.unwrap();
+    Car::insert_many(&[car(3), car(4)], &mut *conn, "cars")
+        .await
+        .unwrap();
+    drop(conn);
+
+    // Transaction with non static lifetime
+    let mut conn = pool.acquire().await.unwrap();
+    let mut tx = sqlx::Acquire::begin(&mut *conn).await.unwrap();
+    insert_in_transaction(&mut tx, &car(5)).await;
+    Car::insert_many(&[car(6)], &mut tx, "cars").await.unwrap();
+    tx.commit().await.unwrap();
+    drop(conn);
+
+    let rows = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 6);
+}
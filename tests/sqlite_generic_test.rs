@@ -0,0 +1,68 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, std::cmp::PartialEq, sqlx::FromRow)]
+struct Car {
+    pub id: i32,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct CreateCar {
+    pub name: String,
+    pub color: Option<String>,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_generic() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        color TEXT DEFAULT 'white'
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let car_skoda = CreateCar {
+        name: "Skoda".to_string(),
+        color: Some("red".to_string()),
+    };
+    let car_res = car_skoda
+        .insert::<Car>(&pool, "cars")
+        .await
+        .expect("Not possible to insert into database");
+    assert_eq!(
+        car_res,
+        Car {
+            id: 1,
+            name: "Skoda".to_string(),
+            color: Some("red".to_string()),
+        }
+    );
+
+    let car_tesla = CreateCar {
+        name: "Tesla".to_string(),
+        color: None,
+    };
+    let car_res = car_tesla
+        .insert_by_rowid::<Car>(&pool, "cars")
+        .await
+        .expect("Not possible to insert into database");
+    assert_eq!(
+        car_res,
+        Car {
+            id: 2,
+            name: "Tesla".to_string(),
+            color: None,
+        }
+    );
+}
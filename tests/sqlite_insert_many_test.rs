@@ -0,0 +1,50 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Car {
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_many() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    assert_eq!(
+        Car::insert_many_query("cars", 2),
+        r#"insert into "cars" ( "car_id", "car_name" ) values ( $1,$2 ),( $3,$4 )"#
+    );
+
+    // More bind parameters than SQLite allows in one query
+    let cars: Vec<Car> = (1..20_001)
+        .map(|car_id| Car {
+            car_id,
+            car_name: format!("Skoda {}", car_id),
+        })
+        .collect();
+
+    let res = Car::insert_many(&cars, &pool, "cars").await.unwrap();
+    assert_eq!(res.rows_affected(), 20_000);
+    assert_eq!(res.last_insert_rowid(), 20_000);
+
+    let count: (i64,) = sqlx::query_as("SELECT count(*) FROM cars")
+        .fetch_one(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(count.0, 20_000);
+}
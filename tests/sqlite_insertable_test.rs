@@ -0,0 +1,124 @@
+#![cfg(feature = "sqlite")]
+
+use sqlxinsert::Insertable;
+
+#[derive(Default, Debug, std::cmp::PartialEq, sqlx::FromRow)]
+struct Car {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "cars")]
+struct CreateCar {
+    pub name: String,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct CreateBike {
+    #[sqlxinsert(rename = "type")]
+    pub kind: String,
+}
+
+// Only trait impl so own inherent methods do not conflict
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "cars", trait_only)]
+struct ImportCar {
+    pub name: String,
+}
+
+impl ImportCar {
+    pub fn insert_query(&self) -> String {
+        format!("insert into cars ( name ) values ( '{}' )", self.name)
+    }
+}
+
+/// Generic repository code over any insertable struct
+async fn save<M: Insertable<sqlx::Sqlite>>(
+    model: &M,
+    pool: &sqlx::SqlitePool,
+) -> sqlxinsert::Result<Car> {
+    model.insert(pool).await
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insertable() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    assert_eq!(<CreateCar as Insertable<sqlx::Sqlite>>::TABLE, Some("cars"));
+    assert_eq!(
+        <CreateCar as Insertable<sqlx::Sqlite>>::COLUMNS,
+        &[r#""name""#]
+    );
+    assert_eq!(<CreateBike as Insertable<sqlx::Sqlite>>::TABLE, None);
+    assert_eq!(
+        <CreateBike as Insertable<sqlx::Sqlite>>::COLUMNS,
+        &["\"type\""]
+    );
+
+    let car_skoda = CreateCar {
+        name: "Skoda".to_string(),
+    };
+    let car = save(&car_skoda, &pool).await.unwrap();
+    assert_eq!(
+        car,
+        Car {
+            id: 1,
+            name: "Skoda".to_string(),
+        }
+    );
+
+    // Columns and binds used by custom query
+    let sql = format!(
+        "insert into cars ( {} ) values ( $1 )",
+        <CreateCar as Insertable<sqlx::Sqlite>>::COLUMNS.join(", ")
+    );
+    let car_tesla = CreateCar {
+        name: "Tesla".to_string(),
+    };
+    car_tesla
+        .bind_all(sqlx::query(&sql))
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let import = ImportCar {
+        name: "Volvo".to_string(),
+    };
+    assert_eq!(
+        import.insert_query(),
+        "insert into cars ( name ) values ( 'Volvo' )"
+    );
+    let car = save(&import, &pool).await.unwrap();
+    assert_eq!(car.id, 3);
+    assert_eq!(car.name, "Volvo");
+
+    // Table is required without `#[sqlxinsert(table = "...")]`
+    let bike = CreateBike {
+        kind: "BMX".to_string(),
+    };
+    assert!(Insertable::<sqlx::Sqlite>::insert::<Car>(&bike, &pool)
+        .await
+        .is_err());
+
+    let cars: Vec<Car> = sqlx::query_as("select * from cars order by id")
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+    assert_eq!(cars.len(), 3);
+}
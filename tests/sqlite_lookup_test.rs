@@ -0,0 +1,68 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert, sqlxinsert::SqliteCrud)]
+#[sqlxinsert(table = "users")]
+struct User {
+    #[sqlxinsert(primary_key)]
+    pub user_id: i32,
+    #[sqlxinsert(unique)]
+    pub email: String,
+    #[sqlxinsert(lookup)]
+    pub team_id: i64,
+    #[sqlxinsert(lookup, rename = "type")]
+    pub r#type: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_lookup() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table users (
+        user_id INTEGER PRIMARY KEY,
+        email TEXT NOT NULL UNIQUE,
+        team_id INTEGER NOT NULL,
+        type TEXT NOT NULL
+    )";
+    sqlx::query(create_table).execute(&pool).await.unwrap();
+
+    let users = [
+        (3, "c@example.com", 1, "admin"),
+        (1, "a@example.com", 1, "user"),
+        (2, "b@example.com", 2, "user"),
+    ];
+    for (user_id, email, team_id, kind) in users {
+        let user = User {
+            user_id,
+            email: email.to_string(),
+            team_id,
+            r#type: kind.to_string(),
+        };
+        user.insert_raw(&pool).await.unwrap();
+    }
+
+    let user = User::find_by_email::<User>(&pool, "b@example.com")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(user.user_id, 2);
+    let user = User::find_by_email::<User>(&pool, "x@example.com")
+        .await
+        .unwrap();
+    assert!(user.is_none());
+
+    let team: Vec<User> = User::find_all_by_team_id::<User>(&pool, 1).await.unwrap();
+    let ids: Vec<i32> = team.iter().map(|user| user.user_id).collect();
+    assert_eq!(ids, vec![1, 3]);
+
+    let users: Vec<User> = User::find_all_by_type_in::<User>(&pool, "user", "users")
+        .await
+        .unwrap();
+    let emails: Vec<&str> = users.iter().map(|user| user.email.as_str()).collect();
+    assert_eq!(emails, vec!["a@example.com", "b@example.com"]);
+}
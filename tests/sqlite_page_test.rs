@@ -0,0 +1,103 @@
+#![cfg(feature = "sqlite")]
+
+use sqlxinsert::Page;
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert, sqlxinsert::SqliteCrud)]
+#[sqlxinsert(table = "cars")]
+struct Car {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteCrud)]
+struct CarByName {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    #[sqlxinsert(order_key)]
+    pub car_name: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_page() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars ( car_id INTEGER PRIMARY KEY, car_name TEXT NOT NULL )";
+    sqlx::query(create_table).execute(&pool).await.unwrap();
+
+    let names = ["Skoda", "Tesla", "Audi", "Fiat", "BMW"];
+    for (i, name) in names.iter().enumerate() {
+        let car = Car {
+            car_id: i as i32 + 1,
+            car_name: name.to_string(),
+        };
+        car.insert_raw(&pool).await.unwrap();
+    }
+
+    fn ids<C>(page: &Page<Car, C>) -> Vec<i32> {
+        page.items.iter().map(|car| car.car_id).collect()
+    }
+
+    // Keyset on primary key
+    let page = Car::page::<Car>(&pool, None, 2).await.unwrap();
+    assert_eq!(ids(&page), vec![1, 2]);
+    assert_eq!(page.next, Some(2));
+    let page = Car::page::<Car>(&pool, page.next, 2).await.unwrap();
+    assert_eq!(ids(&page), vec![3, 4]);
+    let page = Car::page::<Car>(&pool, page.next, 2).await.unwrap();
+    assert_eq!(ids(&page), vec![5]);
+    assert_eq!(page.next, None);
+
+    // Page ending exactly at last row has no next page
+    let page = Car::page::<Car>(&pool, Some(3), 2).await.unwrap();
+    assert_eq!(ids(&page), vec![4, 5]);
+    assert_eq!(page.next, None);
+
+    // Offset
+    let page = Car::page_offset::<Car>(&pool, 0, 3).await.unwrap();
+    assert_eq!(ids(&page), vec![1, 2, 3]);
+    assert_eq!(page.next, Some(3));
+    let page = Car::page_offset::<Car>(&pool, 3, 3).await.unwrap();
+    assert_eq!(ids(&page), vec![4, 5]);
+    assert_eq!(page.next, None);
+
+    // Empty page for zero or negative limit
+    let page = Car::page::<Car>(&pool, None, 0).await.unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.next, None);
+    let page = Car::page::<Car>(&pool, Some(2), -1).await.unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.next, None);
+    let page = Car::page_offset::<Car>(&pool, 1, 0).await.unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.next, None);
+    let page = Car::page_offset::<Car>(&pool, 0, i64::MIN).await.unwrap();
+    assert!(page.items.is_empty());
+    assert_eq!(page.next, None);
+
+    // Negative offset starts at first row
+    let page = Car::page_offset::<Car>(&pool, -5, 2).await.unwrap();
+    assert_eq!(ids(&page), vec![1, 2]);
+    assert_eq!(page.next, Some(2));
+
+    // Keyset on order key
+    let page = CarByName::page::<Car>(&pool, None, 3, "cars")
+        .await
+        .unwrap();
+    let names: Vec<&str> = page.items.iter().map(|car| car.car_name.as_str()).collect();
+    assert_eq!(names, vec!["Audi", "BMW", "Fiat"]);
+    assert_eq!(page.next.as_deref(), Some("Fiat"));
+    let page = CarByName::page::<CarByName>(&pool, page.next, 3, "cars")
+        .await
+        .unwrap();
+    let names: Vec<&str> = page.items.iter().map(|car| car.car_name.as_str()).collect();
+    assert_eq!(names, vec!["Skoda", "Tesla"]);
+    assert_eq!(page.items[0].car_id, 1);
+    assert_eq!(page.next, None);
+}
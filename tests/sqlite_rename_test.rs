@@ -0,0 +1,94 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Car {
+    pub car_id: i32,
+    #[sqlxinsert(rename = "name")]
+    pub car_name: String,
+    #[sqlx(rename = "order")]
+    pub position: i32,
+    pub r#type: String,
+}
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlx(rename_all = "camelCase")]
+struct Owner {
+    pub owner_id: i32,
+    pub first_name: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_rename() {
+    let car = Car {
+        car_id: 33,
+        car_name: "Skoda".to_string(),
+        position: 2,
+        r#type: "combi".to_string(),
+    };
+
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = r#"create table cars (
+        car_id INTEGER PRIMARY KEY,
+        name TEXT NOT NULL,
+        "order" INTEGER NOT NULL,
+        type TEXT NOT NULL
+    )"#;
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    assert_eq!(
+        car.insert_query("cars"),
+        r#"insert into "cars" ( "car_id", "name", "order", "type" ) values ( $1,$2,$3,$4 )"#
+    );
+    car.insert_raw(&pool, "cars").await.unwrap();
+
+    let rows =
+        sqlx::query_as::<_, (String, i32, String)>(r#"SELECT name, "order", type FROM cars"#)
+            .fetch_all(&pool)
+            .await
+            .expect("Not possible to fetch");
+
+    assert_eq!(rows, vec![("Skoda".to_string(), 2, "combi".to_string())]);
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_rename_all() {
+    let owner = Owner {
+        owner_id: 1,
+        first_name: "Filip".to_string(),
+    };
+
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = r#"create table owners (
+        "ownerId" INTEGER PRIMARY KEY,
+        "firstName" TEXT NOT NULL
+    )"#;
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    owner.insert_raw(&pool, "owners").await.unwrap();
+
+    let rows = sqlx::query_as::<_, Owner>("SELECT * FROM owners")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].first_name, "Filip");
+}
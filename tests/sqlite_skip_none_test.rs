@@ -0,0 +1,53 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(skip_none)]
+struct Car {
+    pub car_id: Option<i32>,
+    pub car_name: Option<String>,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_skip_none() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL DEFAULT 'Skoda'
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let car = Car {
+        car_id: None,
+        car_name: Some("Tesla".to_string()),
+    };
+    assert_eq!(
+        car.insert_query("cars"),
+        r#"insert into "cars" ( "car_name" ) values ( $1 )"#
+    );
+    car.insert_raw(&pool, "cars").await.unwrap();
+
+    let car = Car::default();
+    assert_eq!(
+        car.insert_query("cars"),
+        r#"insert into "cars" default values"#
+    );
+    car.insert_raw(&pool, "cars").await.unwrap();
+
+    let rows = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].car_name.as_deref(), Some("Tesla"));
+    assert_eq!(rows[1].car_name.as_deref(), Some("Skoda"));
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "cars")]
+struct Car {
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_insert_table() {
+    let car = Car {
+        car_id: 33,
+        car_name: "Skoda".to_string(),
+    };
+
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    for table in ["cars", "old_cars"] {
+        let create_table = format!(
+            "create table {} (
+                car_id INTEGER PRIMARY KEY,
+                car_name TEXT NOT NULL
+            )",
+            table
+        );
+        sqlx::query(&create_table)
+            .execute(&pool)
+            .await
+            .expect("Not possible to execute");
+    }
+
+    assert_eq!(Car::TABLE, "cars");
+    assert_eq!(
+        Car::INSERT_SQL,
+        r#"insert into "cars" ( "car_id", "car_name" ) values ( $1,$2 )"#
+    );
+    assert_eq!(
+        Car::INSERT_RETURNING_SQL,
+        r#"insert into "cars" ( "car_id", "car_name" ) values ( $1,$2 ) returning *"#
+    );
+    assert_eq!(car.insert_query(), Car::INSERT_SQL);
+
+    // Statements of dynamic tables are built once
+    let first = car.insert_query_in("old_cars");
+    let second = car.insert_query_in("old_cars");
+    assert_eq!(
+        first,
+        r#"insert into "old_cars" ( "car_id", "car_name" ) values ( $1,$2 )"#
+    );
+    assert!(std::ptr::eq(first.as_ref(), second.as_ref()));
+
+    let res = car.insert_raw(&pool).await.unwrap();
+    assert_eq!(res.last_insert_rowid(), 33);
+
+    let res = car.insert_raw_in(&pool, "old_cars").await.unwrap();
+    assert_eq!(res.last_insert_rowid(), 33);
+
+    let rows = sqlx::query_as::<_, Car>("SELECT * FROM old_cars")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].car_name, "Skoda");
+
+    let car = Car {
+        car_id: 34,
+        car_name: "Tesla".to_string(),
+    };
+    let res: Car = car.insert::<Car>(&pool).await.unwrap();
+    assert_eq!(res.car_name, "Tesla");
+}
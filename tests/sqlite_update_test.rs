@@ -0,0 +1,58 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Car {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    pub car_name: String,
+    #[sqlxinsert(skip_none)]
+    pub color: Option<String>,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_update() {
+    let mut car = Car {
+        car_id: 33,
+        car_name: "Skoda".to_string(),
+        color: Some("red".to_string()),
+    };
+
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL,
+        color TEXT
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    car.insert_raw(&pool, "cars").await.unwrap();
+
+    car.car_name = "Tesla".to_string();
+    car.color = None;
+    assert_eq!(
+        car.update_query("cars"),
+        r#"update "cars" set "car_name" = $1 where "car_id" = $2"#
+    );
+
+    let res = car.update(&pool, "cars").await.unwrap();
+    assert_eq!(res.rows_affected(), 1);
+
+    let rows = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].car_name, "Tesla");
+    // skip_none keeps current value
+    assert_eq!(rows[0].color.as_deref(), Some("red"));
+}
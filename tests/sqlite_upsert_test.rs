@@ -0,0 +1,58 @@
+#![cfg(feature = "sqlite")]
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+struct Car {
+    #[sqlxinsert(conflict_key)]
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_upsert() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let car = Car {
+        car_id: 33,
+        car_name: "Skoda".to_string(),
+    };
+    assert_eq!(
+        car.upsert_query("cars"),
+        r#"insert into "cars" ( "car_id", "car_name" ) values ( $1,$2 ) on conflict ( "car_id" ) do update set "car_name" = excluded."car_name""#
+    );
+    car.upsert(&pool, "cars").await.unwrap();
+
+    let car = Car {
+        car_id: 33,
+        car_name: "Tesla".to_string(),
+    };
+    car.upsert(&pool, "cars").await.unwrap();
+
+    let car = Car {
+        car_id: 33,
+        car_name: "Volvo".to_string(),
+    };
+    let res = car.insert_or_ignore(&pool, "cars").await.unwrap();
+    assert_eq!(res.rows_affected(), 0);
+
+    let rows = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_all(&pool)
+        .await
+        .expect("Not possible to fetch");
+
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].car_name, "Tesla");
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "sqlite")]
+
+use sqlxinsert::InsertError;
+
+#[derive(Default, Debug, sqlx::FromRow, sqlxinsert::SqliteInsert)]
+#[sqlxinsert(table = "cars")]
+struct Car {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    pub car_name: String,
+    #[sqlxinsert(version)]
+    pub version: i64,
+}
+
+#[tokio::test]
+async fn test_macro_sqlite_version() {
+    let url = "sqlite::memory:";
+
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .expect("Not possible to create pool");
+
+    let create_table = "create table cars (
+        car_id INTEGER PRIMARY KEY,
+        car_name TEXT NOT NULL,
+        version INTEGER NOT NULL
+    )";
+    sqlx::query(create_table)
+        .execute(&pool)
+        .await
+        .expect("Not possible to execute");
+
+    let car = Car {
+        car_id: 33,
+        car_name: "Skoda".to_string(),
+        version: 0,
+    };
+    car.insert_raw(&pool).await.unwrap();
+
+    let mut first = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    let mut second = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(first.version, 0);
+
+    first.car_name = "Tesla".to_string();
+    assert_eq!(
+        first.update_query(),
+        r#"update "cars" set "car_name" = $1, "version" = "version" + 1 where "car_id" = $2 and "version" = $3"#
+    );
+    let res = first.update(&pool).await.unwrap();
+    assert_eq!(res.rows_affected(), 1);
+
+    // Second reader still has version 0
+    second.car_name = "Volvo".to_string();
+    let res = second.update(&pool).await;
+    assert!(matches!(res, Err(InsertError::StaleObject)));
+
+    let car = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(car.car_name, "Tesla");
+    assert_eq!(car.version, 1);
+
+    // Reload and retry
+    let mut second = car;
+    second.car_name = "Volvo".to_string();
+    second.update(&pool).await.unwrap();
+
+    let car = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(car.car_name, "Volvo");
+    assert_eq!(car.version, 2);
+
+    // Upsert of stale row does not roll version back
+    let stale = Car {
+        car_id: 33,
+        car_name: "Trabant".to_string(),
+        version: 1,
+    };
+    assert_eq!(
+        stale.upsert_query(),
+        r#"insert into "cars" ( "car_id", "car_name", "version" ) values ( $1,$2,$3 ) on conflict ( "car_id" ) do update set "car_name" = excluded."car_name", "version" = "cars"."version" + 1 where "cars"."version" = excluded."version""#
+    );
+    let res = stale.upsert(&pool).await;
+    assert!(matches!(res, Err(InsertError::StaleObject)));
+
+    let current = Car {
+        car_id: 33,
+        car_name: "Trabant".to_string(),
+        version: 2,
+    };
+    current.upsert(&pool).await.unwrap();
+
+    let car = sqlx::query_as::<_, Car>("SELECT * FROM cars")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(car.car_name, "Trabant");
+    assert_eq!(car.version, 3);
+}
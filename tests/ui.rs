@@ -0,0 +1,10 @@
+#![cfg(all(feature = "sqlite", feature = "postgres"))]
+
+/// Compile errors of derives on unsupported structs and attributes
+///
+/// `TRYBUILD=overwrite cargo test --test ui` regenerates expected messages
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+#[derive(sqlxinsert::PgInsert)]
+struct Car {
+    #[sqlxinsert(default)]
+    pub car_id: i32,
+}
+
+fn main() {
+    let _ = Car::copy_in_query("cars");
+}
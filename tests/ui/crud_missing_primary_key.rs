@@ -0,0 +1,7 @@
+#[derive(sqlxinsert::SqliteCrud)]
+struct Car {
+    pub car_id: i32,
+    pub car_name: String,
+}
+
+fn main() {}
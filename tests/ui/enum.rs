@@ -0,0 +1,7 @@
+#[derive(sqlxinsert::SqliteInsert)]
+enum Car {
+    Skoda,
+    Tesla,
+}
+
+fn main() {}
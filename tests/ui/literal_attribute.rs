@@ -0,0 +1,7 @@
+#[derive(sqlxinsert::SqliteInsert)]
+#[sqlxinsert("cars")]
+struct Car {
+    pub car_id: i32,
+}
+
+fn main() {}
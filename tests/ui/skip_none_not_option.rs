@@ -0,0 +1,8 @@
+#[derive(sqlxinsert::PgInsert)]
+struct Car {
+    pub car_id: i32,
+    #[sqlxinsert(skip_none)]
+    pub car_name: String,
+}
+
+fn main() {}
@@ -0,0 +1,4 @@
+#[derive(sqlxinsert::SqliteInsert)]
+struct Car(i32, String);
+
+fn main() {}
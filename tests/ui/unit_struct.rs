@@ -0,0 +1,4 @@
+#[derive(sqlxinsert::PgInsert)]
+struct Car;
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(sqlxinsert::PgInsert)]
+struct Car {
+    #[sqlxinsert(primary)]
+    pub car_id: i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[derive(sqlxinsert::PgInsert)]
+struct Car {
+    #[sqlxinsert(primary_key)]
+    pub car_id: i32,
+    #[sqlxinsert(version)]
+    pub car_name: String,
+}
+
+fn main() {}
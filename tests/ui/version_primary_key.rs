@@ -0,0 +1,8 @@
+#[derive(sqlxinsert::SqliteInsert)]
+struct Car {
+    #[sqlxinsert(primary_key, version)]
+    pub version: i64,
+    pub car_name: String,
+}
+
+fn main() {}